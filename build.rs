@@ -1,41 +1,274 @@
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "bindgen")]
 use bindgen::callbacks::ParseCallbacks;
 
 fn main() {
-    // First ensure that appropriate version of HarfBuzz exists
-    let include_paths = if cfg!(feature = "bundled") {
-        build_harfbuzz()
+    // First ensure that appropriate version of HarfBuzz exists. There is no system HarfBuzz on WebAssembly.
+    let (include_paths, version, bundled) = if cfg!(feature = "bundled") || is_wasm() {
+        (build_harfbuzz(), bundled_version(), true)
     } else {
-        pkg_config::probe_library("harfbuzz-subset")
-            .unwrap()
-            .include_paths
+        match pkg_config::Config::new()
+            .atleast_version(MIN_VERSION)
+            .probe("harfbuzz-subset")
+        {
+            Ok(library) => (
+                library.include_paths,
+                parse_version(&library.version),
+                false,
+            ),
+            Err(err) if Path::new(BUNDLED_SOURCE).exists() => {
+                println!("cargo:warning=Using bundled HarfBuzz, as system HarfBuzz is not usable: {err}");
+                (build_harfbuzz(), bundled_version(), true)
+            }
+            Err(err) => panic!(
+                "HarfBuzz {MIN_VERSION} or newer was not found, and the bundled copy is not available: {err}"
+            ),
+        }
     };
-    // Then build the sys bindings
-    build_bindings(include_paths);
+    // Then tell the crate what the headers provide, and build the sys bindings
+    emit_capability_cfgs(version, bundled);
+    println!("cargo:rustc-check-cfg=cfg(hb_subset_rust_allocator)");
+    println!("cargo:rustc-check-cfg=cfg(hb_subset_lean)");
+    #[cfg(feature = "bindgen")]
+    build_bindings(include_paths, bundled);
+    #[cfg(not(feature = "bindgen"))]
+    {
+        let _ = (include_paths, bundled);
+        copy_pregenerated_bindings();
+    }
+}
+
+/// Bindings generated against the bundled HarfBuzz with experimental APIs enabled.
+///
+/// These are used unless feature `bindgen` is enabled. HarfBuzz only ever adds to its API, so bindings generated
+/// against the bundled version work with all supported versions, as long as the functions missing from older versions
+/// are not called. The capability cfgs take care of that. To regenerate the bindings, run
+/// `HB_SUBSET_UPDATE_BINDINGS=1 cargo build --features bindgen` with the HarfBuzz submodule checked out.
+const PREGENERATED_BINDINGS: &str = "bindings/harfbuzz.rs";
+
+#[cfg(not(feature = "bindgen"))]
+fn copy_pregenerated_bindings() {
+    println!("cargo:rerun-if-changed={PREGENERATED_BINDINGS}");
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::copy(PREGENERATED_BINDINGS, out_path.join("bindings.rs")).unwrap_or_else(|err| {
+        panic!("Pre-generated bindings {PREGENERATED_BINDINGS} are not available ({err}), enable feature `bindgen`")
+    });
+}
+
+/// Checks whether the crate is being built for WebAssembly.
+fn is_wasm() -> bool {
+    env::var("CARGO_CFG_TARGET_ARCH").unwrap() == "wasm32"
+}
+
+/// Checks whether the crate is being built for WebAssembly without WASI, e.g. for browsers.
+fn is_wasm_without_os() -> bool {
+    is_wasm() && env::var("CARGO_CFG_TARGET_OS").unwrap() == "unknown"
+}
+
+/// Gets the WASI sysroot providing the C and C++ headers, and the C library, for WebAssembly builds.
+///
+/// This is e.g. `share/wasi-sysroot` of [wasi-sdk](https://github.com/WebAssembly/wasi-sdk).
+fn wasi_sysroot() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=WASI_SYSROOT");
+    env::var_os("WASI_SYSROOT").map(PathBuf::from)
+}
+
+/// The oldest supported version of HarfBuzz.
+const MIN_VERSION: &str = "7.0.0";
+
+/// The amalgamated source of the bundled HarfBuzz, which contains all of HarfBuzz including the subsetter.
+const BUNDLED_SOURCE: &str = "harfbuzz/src/harfbuzz-subset.cc";
+
+/// Cfgs for capabilities which depend on the version of HarfBuzz, and the version which introduced each of them.
+///
+/// Keep in sync with `Capability` in `src/version.rs`.
+const CAPABILITIES: &[(&str, (u32, u32, u32))] = &[
+    ("hb_subset_no_layout_closure", (7, 2, 0)),
+    ("hb_subset_override_name_table", (7, 0, 0)),
+];
+
+/// Checks whether the bundled HarfBuzz is built with `HB_LEAN`, which leaves out some APIs this crate wraps.
+fn is_lean() -> bool {
+    cfg!(feature = "bundled-lean") && !cfg!(feature = "bundled-full")
+}
+
+fn emit_capability_cfgs(version: Option<(u32, u32, u32)>, bundled: bool) {
+    let version = version.unwrap_or_else(|| {
+        println!("cargo:warning=Could not detect HarfBuzz version, assuming the oldest supported one, {MIN_VERSION}");
+        parse_version(MIN_VERSION).unwrap()
+    });
+    for &(cfg, min_version) in CAPABILITIES {
+        println!("cargo:rustc-check-cfg=cfg({cfg})");
+        if cfg == "hb_subset_override_name_table"
+            && !(bundled && cfg!(feature = "experimental") && !is_lean())
+        {
+            // Experimental APIs exist only in builds with HB_EXPERIMENTAL_API. Only the bundled build is known to be
+            // one, distributions usually leave them out. HB_LEAN leaves out the name table support they need.
+            continue;
+        }
+        if version >= min_version {
+            println!("cargo:rustc-cfg={cfg}");
+        }
+    }
+}
+
+/// Parses version strings of form `major.minor.micro`.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split('.').map(|part| part.parse().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let micro = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, micro))
+}
+
+/// Reads the version of the bundled HarfBuzz from its headers.
+fn bundled_version() -> Option<(u32, u32, u32)> {
+    let header = fs::read_to_string("harfbuzz/src/hb-version.h").ok()?;
+    header.lines().find_map(|line| {
+        let version = line.strip_prefix("#define HB_VERSION_STRING")?;
+        parse_version(version.trim().trim_matches('"'))
+    })
 }
 
 fn build_harfbuzz() -> Vec<PathBuf> {
-    cc::Build::new()
+    let mut build = cc::Build::new();
+    build
         .cpp(true)
         .flag("-std=c++11")
         .warnings(false)
-        .file("harfbuzz/src/harfbuzz-subset.cc")
-        .compile("embedded-harfbuzz-subset");
+        .file(BUNDLED_SOURCE);
+    if cfg!(feature = "experimental") {
+        build.define("HB_EXPERIMENTAL_API", None);
+    }
+    if cfg!(feature = "bundled-subset-only") && !cfg!(feature = "bundled-full") {
+        // Leave out the parts of HarfBuzz which the subsetter does not need
+        for define in [
+            "HB_NO_OT_SHAPE",
+            "HB_NO_BUFFER_MESSAGE",
+            "HB_NO_BUFFER_SERIALIZE",
+            "HB_NO_BUFFER_VERIFY",
+        ] {
+            build.define(define, None);
+        }
+    }
+    if cfg!(feature = "bundled-mini") && !cfg!(feature = "bundled-full") {
+        build.define("HB_MINI", None);
+    }
+    if is_lean() {
+        // Also compiles out some APIs, e.g. the name table and variation axes. The crate stubs out their wrappers.
+        build.define("HB_LEAN", None);
+        println!("cargo:rustc-cfg=hb_subset_lean");
+    }
+    if is_wasm() {
+        // There are neither threads nor memory mapping, and no C++ runtime to support exceptions or RTTI
+        for define in [
+            "HB_NO_MT",
+            "HB_NO_MMAP",
+            "HB_NO_ATEXIT",
+            "HB_NO_GETENV",
+            "HB_NO_SETLOCALE",
+        ] {
+            build.define(define, None);
+        }
+        build
+            .flag("-fno-exceptions")
+            .flag("-fno-rtti")
+            .flag("-fno-threadsafe-statics");
+        match wasi_sysroot() {
+            Some(sysroot) => {
+                build.flag(format!("--sysroot={}", sysroot.display()));
+            }
+            None if is_wasm_without_os() => {
+                panic!("Building HarfBuzz for WebAssembly requires the WASI sysroot, set it in WASI_SYSROOT")
+            }
+            None => {}
+        }
+    }
+    if is_wasm_without_os() {
+        // Without WASI there are no files, and the few libc functions HarfBuzz needs are linked statically from
+        // wasi-libc. The linker drops everything else, so the module imports nothing from WASI.
+        build.define("HB_NO_OPEN", None);
+        let sysroot = wasi_sysroot().unwrap();
+        for lib in ["lib/wasm32-wasi", "lib/wasm32-wasip1"] {
+            println!(
+                "cargo:rustc-link-search=native={}",
+                sysroot.join(lib).display()
+            );
+        }
+        println!("cargo:rustc-link-lib=static=c");
+    }
+    if cfg!(feature = "custom-allocator") || is_wasm_without_os() {
+        // Route allocations through src/allocator.rs, which enforces memory budgets. Without WASI, there is no other
+        // allocator.
+        for (function, hook) in [
+            ("hb_malloc_impl", "hb_subset_rs_malloc"),
+            ("hb_calloc_impl", "hb_subset_rs_calloc"),
+            ("hb_realloc_impl", "hb_subset_rs_realloc"),
+            ("hb_free_impl", "hb_subset_rs_free"),
+        ] {
+            build.define(function, hook);
+        }
+        println!("cargo:rustc-cfg=hb_subset_rust_allocator");
+    }
+    build.compile("embedded-harfbuzz-subset");
 
     println!("cargo:rerun-if-changed=harfbuzz/src");
 
     vec!["harfbuzz/src/".into()]
 }
 
-fn build_bindings(include_paths: Vec<PathBuf>) {
-    let bindings = bindgen::Builder::default()
+#[cfg(feature = "bindgen")]
+fn build_bindings(include_paths: Vec<PathBuf>, bundled: bool) {
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let bindings = generate_bindings(include_paths, cfg!(feature = "experimental"));
+    bindings
+        .write_to_file(out_path.join("bindings.rs"))
+        .expect("Couldn't write bindings!");
+
+    // Also generate the bindings the pre-generated ones are made from, so tests can check that those are up to date
+    if !Path::new(BUNDLED_SOURCE).exists() {
+        return;
+    }
+    let bundled_bindings = if bundled && cfg!(feature = "experimental") {
+        bindings
+    } else {
+        generate_bindings(vec!["harfbuzz/src/".into()], true)
+    };
+    bundled_bindings
+        .write_to_file(out_path.join("bundled_bindings.rs"))
+        .expect("Couldn't write bindings!");
+
+    println!("cargo:rerun-if-env-changed=HB_SUBSET_UPDATE_BINDINGS");
+    if env::var_os("HB_SUBSET_UPDATE_BINDINGS").is_some() {
+        fs::create_dir_all(Path::new(PREGENERATED_BINDINGS).parent().unwrap()).unwrap();
+        bundled_bindings
+            .write_to_file(PREGENERATED_BINDINGS)
+            .expect("Couldn't write bindings!");
+    }
+}
+
+/// Generates bindings to the HarfBuzz headers in the include paths, optionally including the experimental APIs.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_paths: Vec<PathBuf>, experimental: bool) -> bindgen::Bindings {
+    let mut builder = bindgen::Builder::default();
+    if experimental {
+        // Experimental APIs are declared in the headers only when this is defined
+        builder = builder.clang_arg("-DHB_EXPERIMENTAL_API");
+    }
+    builder
         .clang_args(
             include_paths
                 .into_iter()
                 .map(|path| format!("-I{}", path.display())),
         )
+        .clang_args(
+            wasi_sysroot()
+                .filter(|_| is_wasm())
+                .map(|sysroot| format!("--sysroot={}", sysroot.display())),
+        )
         .header("wrapper.h")
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .parse_callbacks(Box::new(NoCommentsCallback))
@@ -44,21 +277,20 @@ fn build_bindings(include_paths: Vec<PathBuf>) {
         .bitfield_enum("hb_subset_sets_t")
         .bitfield_enum("hb_ot_name_id_predefined_t")
         .new_type_alias("hb_ot_name_id_t")
+        // Layout tests would tie the pre-generated bindings to the platform they were generated on
+        .layout_tests(false)
         .generate()
-        .expect("Unable to generate bindings");
-
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
+        .expect("Unable to generate bindings")
 }
 
 /// [`ParseCallbacks`] which make Bindgen generate no comments.
 ///
 /// This is because Bindgen does not properly support the style of comments used in the C headers, and hence emitting
 /// them to Rust code adds only unnecessary noise.
+#[cfg(feature = "bindgen")]
 #[derive(Debug)]
 struct NoCommentsCallback;
+#[cfg(feature = "bindgen")]
 impl ParseCallbacks for NoCommentsCallback {
     fn process_comment(&self, _comment: &str) -> Option<String> {
         Some("".into())
@@ -0,0 +1,366 @@
+//! Memory allocator of the bundled HarfBuzz.
+//!
+//! With feature `custom-allocator`, the bundled HarfBuzz is built to allocate all of its memory through this module
+//! instead of the C library. This is always the case on WebAssembly without WASI, which lacks a C library allocator.
+//! This allows limiting the memory a single subsetting operation may use, see
+//! [`SubsetInput::set_memory_budget`](crate::SubsetInput::set_memory_budget).
+//!
+//! By default the allocations are forwarded to the Rust global allocator, so a `#[global_allocator]` of the
+//! application also serves HarfBuzz. Alternatively, the application can install its own [`AllocatorHooks`] with
+//! [`set_allocator_hooks`] before HarfBuzz allocates anything.
+//!
+//! # Example
+//! ```
+//! use std::ffi::c_void;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! use hb_subset::allocator::{self, AllocatorHooks};
+//!
+//! static CALLS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! unsafe extern "C" fn counting_malloc(size: usize) -> *mut c_void {
+//!     CALLS.fetch_add(1, Ordering::Relaxed);
+//!     (AllocatorHooks::GLOBAL.malloc)(size)
+//! }
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! allocator::set_allocator_hooks(AllocatorHooks {
+//!     malloc: counting_malloc,
+//!     ..AllocatorHooks::GLOBAL
+//! })?;
+//! let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+//! hb_subset::subset(&font, "abc".chars())?;
+//! assert!(CALLS.load(Ordering::Relaxed) > 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    alloc::{self, Layout},
+    cell::Cell,
+    ffi::c_void,
+    ptr::null_mut,
+    sync::OnceLock,
+};
+
+use crate::AllocatorHooksError;
+
+/// Functions HarfBuzz uses for allocating memory.
+///
+/// The functions must behave like their counterparts in the C standard library. In particular, memory allocated with
+/// any of them is freed with [`Self::free`], or resized with [`Self::realloc`].
+#[derive(Debug, Clone, Copy)]
+pub struct AllocatorHooks {
+    /// Allocates memory of the given size, like `malloc`.
+    pub malloc: unsafe extern "C" fn(size: usize) -> *mut c_void,
+    /// Allocates zeroed memory for the given number of elements of the given size, like `calloc`.
+    pub calloc: unsafe extern "C" fn(count: usize, size: usize) -> *mut c_void,
+    /// Resizes memory, like `realloc`.
+    pub realloc: unsafe extern "C" fn(ptr: *mut c_void, size: usize) -> *mut c_void,
+    /// Frees memory, like `free`.
+    pub free: unsafe extern "C" fn(ptr: *mut c_void),
+}
+
+impl AllocatorHooks {
+    /// Hooks which forward the allocations to the Rust global allocator. These are used by default.
+    pub const GLOBAL: Self = Self {
+        malloc: global_malloc,
+        calloc: global_calloc,
+        realloc: global_realloc,
+        free: global_free,
+    };
+}
+
+static HOOKS: OnceLock<AllocatorHooks> = OnceLock::new();
+
+/// Installs the hooks HarfBuzz uses for allocating memory.
+///
+/// The hooks can be installed only once, and only before HarfBuzz has allocated anything, as memory must be freed by
+/// the same allocator which allocated it. Otherwise [`AllocatorHooksError`] is returned.
+pub fn set_allocator_hooks(hooks: AllocatorHooks) -> Result<(), AllocatorHooksError> {
+    HOOKS.set(hooks).map_err(|_| AllocatorHooksError)
+}
+
+/// Gets the hooks in use, fixing them to the default ones if none have been installed.
+fn hooks() -> &'static AllocatorHooks {
+    HOOKS.get_or_init(|| AllocatorHooks::GLOBAL)
+}
+
+/// Memory budget of the subsetting operation running on a thread.
+#[derive(Debug, Clone, Copy)]
+struct Budget {
+    limit: usize,
+    used: usize,
+    exceeded: bool,
+}
+
+thread_local! {
+    static BUDGET: Cell<Option<Budget>> = const { Cell::new(None) };
+}
+
+/// Runs the given function with the memory HarfBuzz may allocate on the current thread limited to the given number of
+/// bytes.
+///
+/// Allocations which would exceed the budget fail. Returns the result of the function, and whether any allocation
+/// failed because of the budget.
+pub(crate) fn with_memory_budget<T>(limit: usize, f: impl FnOnce() -> T) -> (T, bool) {
+    /// Restores the outer budget even if the function panics.
+    struct Restore(Option<Budget>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BUDGET.with(|budget| budget.set(self.0));
+        }
+    }
+
+    let budget = Budget {
+        limit,
+        used: 0,
+        exceeded: false,
+    };
+    let restore = Restore(BUDGET.with(|current| current.replace(Some(budget))));
+    let result = f();
+    let budget = BUDGET.with(|budget| budget.get());
+    drop(restore);
+    (result, budget.is_some_and(|budget| budget.exceeded))
+}
+
+/// Accounts an allocation of the given size to the budget. Returns false if the budget does not allow it.
+fn reserve(size: usize) -> bool {
+    BUDGET
+        .try_with(|current| {
+            let Some(mut budget) = current.get() else {
+                return true;
+            };
+            let allowed = budget
+                .used
+                .checked_add(size)
+                .is_some_and(|used| used <= budget.limit);
+            if allowed {
+                budget.used += size;
+            } else {
+                budget.exceeded = true;
+            }
+            current.set(Some(budget));
+            allowed
+        })
+        .unwrap_or(true)
+}
+
+/// Returns memory of the given size to the budget.
+fn release(size: usize) {
+    let _ = BUDGET.try_with(|current| {
+        if let Some(mut budget) = current.get() {
+            budget.used = budget.used.saturating_sub(size);
+            current.set(Some(budget));
+        }
+    });
+}
+
+/// Size of the header which stores the size of each allocation made by HarfBuzz, so that freed memory can be returned
+/// to the budget.
+///
+/// This keeps the alignment `malloc` guarantees on common platforms.
+const SIZE_HEADER: usize = 16;
+
+/// Stores the size in the header of a new allocation, and returns the pointer to the memory after the header.
+unsafe fn with_size_header(ptr: *mut c_void, size: usize) -> *mut c_void {
+    ptr.cast::<usize>().write(size);
+    ptr.cast::<u8>().add(SIZE_HEADER).cast()
+}
+
+/// Gets the pointer to the header of an allocation, and the size stored in it.
+unsafe fn size_header(ptr: *mut c_void) -> (*mut c_void, usize) {
+    let base = ptr.cast::<u8>().sub(SIZE_HEADER);
+    (base.cast(), base.cast::<usize>().read())
+}
+
+#[no_mangle]
+unsafe extern "C" fn hb_subset_rs_malloc(size: usize) -> *mut c_void {
+    let Some(total) = size.checked_add(SIZE_HEADER) else {
+        return null_mut();
+    };
+    if !reserve(size) {
+        return null_mut();
+    }
+    let ptr = (hooks().malloc)(total);
+    if ptr.is_null() {
+        release(size);
+        return null_mut();
+    }
+    with_size_header(ptr, size)
+}
+
+#[no_mangle]
+unsafe extern "C" fn hb_subset_rs_calloc(count: usize, size: usize) -> *mut c_void {
+    let Some(size) = count.checked_mul(size) else {
+        return null_mut();
+    };
+    let Some(total) = size.checked_add(SIZE_HEADER) else {
+        return null_mut();
+    };
+    if !reserve(size) {
+        return null_mut();
+    }
+    let ptr = (hooks().calloc)(1, total);
+    if ptr.is_null() {
+        release(size);
+        return null_mut();
+    }
+    with_size_header(ptr, size)
+}
+
+#[no_mangle]
+unsafe extern "C" fn hb_subset_rs_realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return hb_subset_rs_malloc(size);
+    }
+    let Some(total) = size.checked_add(SIZE_HEADER) else {
+        return null_mut();
+    };
+    let (base, old_size) = size_header(ptr);
+    if size > old_size && !reserve(size - old_size) {
+        return null_mut();
+    }
+    let base = (hooks().realloc)(base, total);
+    if base.is_null() {
+        if size > old_size {
+            release(size - old_size);
+        }
+        return null_mut();
+    }
+    if size < old_size {
+        release(old_size - size);
+    }
+    with_size_header(base, size)
+}
+
+#[no_mangle]
+unsafe extern "C" fn hb_subset_rs_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let (base, size) = size_header(ptr);
+    release(size);
+    (hooks().free)(base)
+}
+
+/// Size of the header which stores the size of each allocation made with the global allocator.
+///
+/// This is also the alignment of the allocations, which matches the alignment `malloc` guarantees on common
+/// platforms.
+const HEADER: usize = 16;
+
+/// Allocates memory with the global allocator, prefixed with a header holding the layout of the allocation.
+unsafe fn global_alloc(size: usize, zeroed: bool) -> *mut c_void {
+    let Some(layout) = global_layout(size) else {
+        return null_mut();
+    };
+    let ptr = if zeroed {
+        alloc::alloc_zeroed(layout)
+    } else {
+        alloc::alloc(layout)
+    };
+    if ptr.is_null() {
+        return null_mut();
+    }
+    ptr.cast::<usize>().write(layout.size());
+    ptr.add(HEADER).cast()
+}
+
+/// Gets the layout of a global allocation of the given size, including the header.
+fn global_layout(size: usize) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(HEADER)?, HEADER).ok()
+}
+
+unsafe extern "C" fn global_malloc(size: usize) -> *mut c_void {
+    global_alloc(size, false)
+}
+
+unsafe extern "C" fn global_calloc(count: usize, size: usize) -> *mut c_void {
+    match count.checked_mul(size) {
+        Some(size) => global_alloc(size, true),
+        None => null_mut(),
+    }
+}
+
+unsafe extern "C" fn global_realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return global_malloc(size);
+    }
+    let Some(new_layout) = global_layout(size) else {
+        return null_mut();
+    };
+    let base = ptr.cast::<u8>().sub(HEADER);
+    let layout = Layout::from_size_align_unchecked(base.cast::<usize>().read(), HEADER);
+    let base = alloc::realloc(base, layout, new_layout.size());
+    if base.is_null() {
+        return null_mut();
+    }
+    base.cast::<usize>().write(new_layout.size());
+    base.add(HEADER).cast()
+}
+
+unsafe extern "C" fn global_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let base = ptr.cast::<u8>().sub(HEADER);
+    let layout = Layout::from_size_align_unchecked(base.cast::<usize>().read(), HEADER);
+    alloc::dealloc(base, layout);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_hooks_allocate_and_resize() {
+        unsafe {
+            let ptr = (AllocatorHooks::GLOBAL.calloc)(4, 8).cast::<u8>();
+            assert!(!ptr.is_null());
+            assert_eq!(ptr as usize % HEADER, 0);
+            assert!((0..32).all(|i| *ptr.add(i) == 0));
+            ptr.write_bytes(7, 32);
+
+            let ptr = (AllocatorHooks::GLOBAL.realloc)(ptr.cast(), 1024).cast::<u8>();
+            assert!(!ptr.is_null());
+            assert!((0..32).all(|i| *ptr.add(i) == 7));
+            (AllocatorHooks::GLOBAL.free)(ptr.cast());
+
+            assert!((AllocatorHooks::GLOBAL.calloc)(usize::MAX, 2).is_null());
+            assert!((AllocatorHooks::GLOBAL.malloc)(usize::MAX).is_null());
+            (AllocatorHooks::GLOBAL.free)(null_mut());
+        }
+    }
+
+    #[test]
+    fn budget_limits_allocations() {
+        let ((), exceeded) = with_memory_budget(1000, || unsafe {
+            let a = hb_subset_rs_malloc(600);
+            assert!(!a.is_null());
+            assert!(hb_subset_rs_calloc(100, 5).is_null());
+            let a = hb_subset_rs_realloc(a, 100);
+            assert!(!a.is_null());
+            let b = hb_subset_rs_calloc(100, 5);
+            assert!(!b.is_null());
+            assert!(hb_subset_rs_realloc(b, 1000).is_null());
+            hb_subset_rs_free(a);
+            let b = hb_subset_rs_realloc(b, 1000);
+            assert!(!b.is_null());
+            hb_subset_rs_free(b);
+        });
+        assert!(exceeded);
+
+        let ((), exceeded) = with_memory_budget(1000, || unsafe {
+            hb_subset_rs_free(hb_subset_rs_malloc(1000));
+        });
+        assert!(!exceeded);
+        assert!(BUDGET.with(|budget| budget.get()).is_none());
+    }
+
+    #[test]
+    fn hooks_cannot_be_replaced_after_use() {
+        crate::SubsetInput::new().unwrap();
+        assert!(set_allocator_hooks(AllocatorHooks::GLOBAL).is_err());
+    }
+}
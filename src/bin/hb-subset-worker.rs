@@ -0,0 +1,12 @@
+//! Worker process for subsetting fonts in isolation. See the `isolated` module of the `hb-subset` crate.
+
+#[cfg(unix)]
+fn main() {
+    hb_subset::isolated::run_worker();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("Subsetting in a worker process is supported only on Unix");
+    std::process::exit(1);
+}
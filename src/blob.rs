@@ -1,8 +1,7 @@
 use std::{
-    ffi::{c_char, CString},
+    ffi::{c_char, c_void},
     marker::PhantomData,
     ops::Deref,
-    os::unix::prelude::OsStrExt,
     path::Path,
     ptr::null_mut,
     slice,
@@ -18,9 +17,16 @@ pub struct Blob<'a>(*mut sys::hb_blob_t, PhantomData<&'a [u8]>);
 
 impl Blob<'static> {
     /// Creates a new blob containing the data from the specified binary font file.
+    ///
+    /// On Unix platforms the file is memory mapped by HarfBuzz. Elsewhere, e.g. on WebAssembly, and with the bundled
+    /// HarfBuzz built with feature `bundled-lean` or `bundled-mini`, the file is read into memory with
+    /// [`std::fs::read`].
     #[doc(alias = "hb_blob_create_from_file")]
     #[doc(alias = "hb_blob_create_from_file_or_fail")]
+    #[cfg(all(unix, not(hb_subset_lean)))]
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AllocationError> {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
         let path =
             CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| AllocationError)?;
 
@@ -30,6 +36,52 @@ impl Blob<'static> {
         }
         Ok(Self(blob, PhantomData))
     }
+
+    /// Creates a new blob containing the data from the specified binary font file.
+    ///
+    /// On Unix platforms the file is memory mapped by HarfBuzz. Elsewhere, e.g. on WebAssembly, and with the bundled
+    /// HarfBuzz built with feature `bundled-lean` or `bundled-mini`, the file is read into memory with
+    /// [`std::fs::read`].
+    #[cfg(any(not(unix), hb_subset_lean))]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AllocationError> {
+        Self::from_vec(std::fs::read(path).map_err(|_| AllocationError)?)
+    }
+
+    /// Creates a new blob which takes the ownership of the given data.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_vec(std::fs::read("tests/fonts/NotoSans.ttf")?)?)?;
+    /// assert_eq!(font.glyph_count(), 4671);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_blob_create")]
+    #[doc(alias = "hb_blob_create_or_fail")]
+    pub fn from_vec(data: Vec<u8>) -> Result<Self, AllocationError> {
+        unsafe extern "C" fn destroy(user_data: *mut c_void) {
+            drop(Box::from_raw(user_data as *mut Vec<u8>));
+        }
+
+        let len = data.len().try_into().map_err(|_| AllocationError)?;
+        let ptr = data.as_ptr() as *const c_char;
+        // HarfBuzz calls destroy also when it fails to create the blob
+        let blob = unsafe {
+            sys::hb_blob_create_or_fail(
+                ptr,
+                len,
+                sys::hb_memory_mode_t_HB_MEMORY_MODE_READONLY,
+                Box::into_raw(Box::new(data)) as *mut c_void,
+                Some(destroy),
+            )
+        };
+        if blob.is_null() {
+            return Err(AllocationError);
+        }
+        Ok(Self(blob, PhantomData))
+    }
 }
 
 impl<'a> Blob<'a> {
@@ -154,6 +206,15 @@ mod tests {
         assert_eq!(correct, &*blob);
     }
 
+    #[test]
+    fn from_vec_owns_data() {
+        let correct = std::fs::read(NOTO_SANS).unwrap();
+        let blob = Blob::from_vec(correct.clone()).unwrap();
+        let clone = blob.clone();
+        drop(blob);
+        assert_eq!(correct, &*clone);
+    }
+
     #[test]
     fn clone_refers_to_same_object() {
         let b1 = Blob::from_bytes(&[1, 2, 3]).unwrap();
@@ -0,0 +1,393 @@
+//! Cache for subsetting results.
+//!
+//! Subsetting the same font for the same input over and over is wasteful. [`SubsetCache`] remembers the produced
+//! subsets, keyed by a hash of the font and a canonical form of the [`SubsetInput`], so that repeated requests skip
+//! the subsetter entirely.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{subset::encoding, FontFace, SubsetInput, SubsettingError};
+
+/// Hash of the data of a font face.
+///
+/// Hashing a large font is not free, so if the same font is used for many requests, consider computing its hash once
+/// and using [`SubsetCache::subset_with_hash`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontHash([u8; 32]);
+
+impl FontHash {
+    /// Computes the hash of the font face.
+    pub fn new(font: &FontFace<'_>) -> Self {
+        Self(Sha256::digest(&*font.underlying_blob()).into())
+    }
+}
+
+impl fmt::Debug for FontHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FontHash({})", Hex(&self.0))
+    }
+}
+
+/// Key identifying a single subsetting result.
+///
+/// The key is a hash over the font data and the canonical form of the subset input, which covers all sets, flags,
+/// the glyph mapping and pinned axes. The [`Display`](fmt::Display) implementation formats the key as a hexadecimal
+/// string, which is suitable e.g. as a file name or an HTTP entity tag.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey([u8; 32]);
+
+impl CacheKey {
+    /// Computes the key for subsetting the font with the given hash using the input.
+    pub fn new(font: &FontHash, input: &SubsetInput) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(font.0);
+        hasher.update(encoding::encode(input));
+        Self(hasher.finalize().into())
+    }
+
+    /// Derives a key for a variant of the same subset, e.g. the subset encoded in another file format.
+    pub fn variant(&self, name: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(self.0);
+        hasher.update(name);
+        Self(hasher.finalize().into())
+    }
+}
+
+impl fmt::Debug for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CacheKey({})", Hex(&self.0))
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Hex(&self.0))
+    }
+}
+
+/// Helper for formatting bytes as hexadecimal.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A cache of subsetting results.
+///
+/// Results are kept in memory in a least-recently-used cache holding at most the given number of subsets. Optionally,
+/// results can also be persisted on disk with [`Self::with_disk_store`], in which case they survive restarts and
+/// evictions from memory.
+///
+/// The cache can be shared between threads.
+///
+/// # Example
+/// ```
+/// # use hb_subset::{*, cache::SubsetCache};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cache = SubsetCache::new(100);
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let font = font.preprocess_for_subsetting();
+///
+/// let mut subset = SubsetInput::new()?;
+/// subset.unicode_set().insert('a');
+/// let first = cache.subset(&font, &subset)?;
+/// let second = cache.subset(&font, &subset)?; // Served from memory
+/// assert_eq!(first, second);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SubsetCache {
+    memory: Mutex<Lru>,
+    disk: Option<PathBuf>,
+}
+
+impl SubsetCache {
+    /// Creates a new cache which keeps at most `capacity` subsets in memory.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            memory: Mutex::new(Lru::new(capacity)),
+            disk: None,
+        }
+    }
+
+    /// Persists subsets also to the given directory.
+    ///
+    /// The directory is created if it does not exist. Each subset is stored as its own file named after its
+    /// [`CacheKey`], followed by a checksum of the subset. Files that are truncated or otherwise do not match their
+    /// checksum are treated as missing.
+    pub fn with_disk_store(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.disk = Some(dir.into());
+        self
+    }
+
+    /// Subsets the font using the given input, or returns an earlier result for the same font and input.
+    ///
+    /// This hashes the whole font. See [`Self::subset_with_hash`] for a way to avoid that.
+    pub fn subset(
+        &self,
+        font: &FontFace<'_>,
+        input: &SubsetInput,
+    ) -> Result<Arc<[u8]>, SubsettingError> {
+        self.subset_with_hash(font, &FontHash::new(font), input)
+    }
+
+    /// Subsets the font using the given input, or returns an earlier result for the same font and input.
+    ///
+    /// The `hash` must have been computed from `font` with [`FontHash::new`].
+    pub fn subset_with_hash(
+        &self,
+        font: &FontFace<'_>,
+        hash: &FontHash,
+        input: &SubsetInput,
+    ) -> Result<Arc<[u8]>, SubsettingError> {
+        let key = CacheKey::new(hash, input);
+        if let Some(data) = self.get(&key) {
+            return Ok(data);
+        }
+
+        let data: Arc<[u8]> = input.subset_font(font)?.underlying_blob().to_vec().into();
+        self.insert(key, data.clone());
+        Ok(data)
+    }
+
+    /// Gets a cached subset, if one exists.
+    pub fn get(&self, key: &CacheKey) -> Option<Arc<[u8]>> {
+        if let Some(data) = self.memory.lock().unwrap().get(key) {
+            return Some(data);
+        }
+        let path = self.disk_path(key)?;
+        let Some(data) = fs::read(&path).ok().and_then(verify_checksum) else {
+            // Leave missing files alone, but remove damaged ones so that they get rewritten
+            if path.exists() {
+                let _ = fs::remove_file(path);
+            }
+            return None;
+        };
+        let data: Arc<[u8]> = data.into();
+        self.memory.lock().unwrap().insert(*key, data.clone());
+        Some(data)
+    }
+
+    /// Stores a subset in the cache.
+    ///
+    /// Failures to write to the disk store are ignored, as the cache is only an optimization.
+    pub fn insert(&self, key: CacheKey, data: Arc<[u8]>) {
+        if let Some(path) = self.disk_path(&key) {
+            let _ = write_atomically(path, &data);
+        }
+        self.memory.lock().unwrap().insert(key, data);
+    }
+
+    /// Removes all subsets from memory.
+    ///
+    /// The disk store is left untouched.
+    pub fn clear(&self) {
+        self.memory.lock().unwrap().clear();
+    }
+
+    fn disk_path(&self, key: &CacheKey) -> Option<PathBuf> {
+        Some(self.disk.as_ref()?.join(format!("{key}.font")))
+    }
+}
+
+/// Writes the data followed by its checksum so that concurrent readers never observe partially written data.
+///
+/// Each write goes through its own temporary file, so concurrent writers of the same file, whether in this or other
+/// processes, never write into each other's files.
+fn write_atomically(path: PathBuf, data: &[u8]) -> io::Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension(format!(
+        "tmp{}-{}",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = fs::write(&tmp, [data, &Sha256::digest(data)].concat())
+        .and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(tmp);
+    }
+    result
+}
+
+/// Strips the checksum written by [`write_atomically`], or returns [`None`] if the data does not match it.
+fn verify_checksum(mut data: Vec<u8>) -> Option<Vec<u8>> {
+    let len = data.len().checked_sub(32)?;
+    if Sha256::digest(&data[..len])[..] != data[len..] {
+        return None;
+    }
+    data.truncate(len);
+    Some(data)
+}
+
+/// Simple least-recently-used map.
+struct Lru {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<CacheKey, (Arc<[u8]>, u64)>,
+    /// Keys ordered by their last use.
+    order: BTreeMap<u64, CacheKey>,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<Arc<[u8]>> {
+        self.tick += 1;
+        let (data, last_use) = self.entries.get_mut(key)?;
+        self.order.remove(last_use);
+        *last_use = self.tick;
+        self.order.insert(self.tick, *key);
+        Some(data.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, data: Arc<[u8]>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_use)) = self.entries.insert(key, (data, self.tick)) {
+            self.order.remove(&last_use);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > self.capacity {
+            let (_, oldest) = self.order.pop_first().unwrap();
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob};
+
+    fn key(n: u8) -> CacheKey {
+        CacheKey([n; 32])
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut lru = Lru::new(2);
+        lru.insert(key(1), Arc::from([1u8].as_slice()));
+        lru.insert(key(2), Arc::from([2u8].as_slice()));
+        assert!(lru.get(&key(1)).is_some());
+        lru.insert(key(3), Arc::from([3u8].as_slice()));
+        assert!(lru.get(&key(1)).is_some());
+        assert!(lru.get(&key(2)).is_none());
+        assert!(lru.get(&key(3)).is_some());
+    }
+
+    #[test]
+    fn cache_returns_same_subset() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let cache = SubsetCache::new(10);
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+
+        let first = cache.subset(&font, &input).unwrap();
+        let second = cache.subset(&font, &input).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            &*first,
+            &*input.subset_font(&font).unwrap().underlying_blob()
+        );
+
+        input.unicode_set().insert('b');
+        let third = cache.subset(&font, &input).unwrap();
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn disk_store_survives_clearing() {
+        let dir = std::env::temp_dir().join(format!("hb-subset-cache-{}", std::process::id()));
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let cache = SubsetCache::new(10).with_disk_store(&dir);
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+
+        let first = cache.subset(&font, &input).unwrap();
+        cache.clear();
+        let key = CacheKey::new(&FontHash::new(&font), &input);
+        assert!(dir.join(format!("{key}.font")).exists());
+        let second = cache.get(&key).unwrap();
+        assert_eq!(first, second);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn damaged_disk_entries_are_misses() {
+        let dir = std::env::temp_dir().join(format!("hb-subset-damaged-{}", std::process::id()));
+        let cache = SubsetCache::new(10).with_disk_store(&dir);
+        cache.insert(key(1), Arc::from(b"font data".as_slice()));
+        cache.clear();
+        assert_eq!(&*cache.get(&key(1)).unwrap(), b"font data");
+
+        // A truncated file, as if the write had been interrupted
+        let path = dir.join(format!("{}.font", key(1)));
+        let data = fs::read(&path).unwrap();
+        fs::write(&path, &data[..data.len() - 1]).unwrap();
+        cache.clear();
+        assert!(cache.get(&key(1)).is_none());
+        assert!(!path.exists());
+
+        fs::write(&path, b"short").unwrap();
+        assert!(cache.get(&key(1)).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_writers_do_not_share_temporary_files() {
+        let dir = std::env::temp_dir().join(format!("hb-subset-writers-{}", std::process::id()));
+        let cache = SubsetCache::new(0).with_disk_store(&dir);
+        std::thread::scope(|scope| {
+            for n in 0..8u8 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        cache.insert(key(1), Arc::from(vec![n; 4096]));
+                    }
+                });
+            }
+        });
+        let data = cache.get(&key(1)).unwrap();
+        assert_eq!(data.len(), 4096);
+        assert!(data.iter().all(|&byte| byte == data[0]));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -6,13 +6,28 @@ use std::{
     str::FromStr,
 };
 
-use crate::{sys, AllocationError};
+use crate::{sys, AllocationError, InvalidScriptError, InvalidTagError};
 
 /// Four byte integers, each byte representing a character.
 ///
 /// Tags are used to identify tables, design-variation axes, scripts, languages, font features, and baselines with
-/// human-readable names.
-#[derive(Clone, Copy)]
+/// human-readable names. Constants for common tags are in [`tags`](crate::tags), and tags can be constructed in
+/// constant contexts with [`tag!`](crate::tag).
+///
+/// Tags can be parsed from strings, which makes it easy to read them from configuration:
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = "DSIG, cvt, GPOS";
+/// let mut subset = SubsetInput::new()?;
+/// subset
+///     .drop_table_tag_set()
+///     .extend(config.split(',').map(|tag| tag.trim().parse()).collect::<Result<Vec<Tag>, _>>()?);
+/// assert!(subset.drop_table_tag_set().contains(tag!("cvt ")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Tag(u32);
 
 impl Tag {
@@ -32,6 +47,113 @@ impl Tag {
     pub fn new(tag: impl Borrow<[u8; 4]>) -> Self {
         Self(u32::from_be_bytes(*tag.borrow()))
     }
+
+    /// Constructs a new tag from bytes in a constant context.
+    ///
+    /// Unlike [`tag!`](crate::tag), this does not check that the tag is [valid](Self::is_valid).
+    pub const fn from_bytes(tag: &[u8; 4]) -> Self {
+        Self(u32::from_be_bytes(*tag))
+    }
+
+    /// Tests whether the tag follows the rules of the OpenType specification.
+    ///
+    /// Valid tags consist of printable ASCII characters, i.e. characters from space to `~`. A tag may be padded with
+    /// trailing spaces, but it must not start with a space, and a space must not be followed by other characters.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::Tag;
+    /// assert!(Tag::new(b"cvt ").is_valid());
+    /// assert!(!Tag::new(b" cvt").is_valid());
+    /// assert!(!Tag::new(b"c\0vt").is_valid());
+    /// ```
+    pub const fn is_valid(self) -> bool {
+        let bytes = self.0.to_be_bytes();
+        if bytes[0] == b' ' {
+            return false;
+        }
+        let mut padding = false;
+        let mut i = 0;
+        while i < 4 {
+            let byte = bytes[i];
+            if byte < b' ' || byte > b'~' || (padding && byte != b' ') {
+                return false;
+            }
+            padding = byte == b' ';
+            i += 1;
+        }
+        true
+    }
+
+    /// Parses a tag from a string of one to four characters, padding it with spaces.
+    const fn parse(tag: &str) -> Result<Self, InvalidTagError> {
+        let bytes = tag.as_bytes();
+        if bytes.is_empty() || bytes.len() > 4 {
+            return Err(InvalidTagError);
+        }
+        let mut padded = [b' '; 4];
+        let mut i = 0;
+        while i < bytes.len() {
+            padded[i] = bytes[i];
+            i += 1;
+        }
+        let tag = Self::from_bytes(&padded);
+        if !tag.is_valid() {
+            return Err(InvalidTagError);
+        }
+        Ok(tag)
+    }
+
+    /// Implementation detail of [`tag!`](crate::tag).
+    #[doc(hidden)]
+    pub const fn parse_or_panic(tag: &str) -> Self {
+        match Self::parse(tag) {
+            Ok(tag) => tag,
+            Err(_) => panic!("invalid tag"),
+        }
+    }
+}
+
+/// Constructs a [`Tag`] from a string literal at compile time.
+///
+/// Tags shorter than four characters are padded with spaces. Invalid tags fail to compile, see [`Tag::is_valid`].
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// const CVT: Tag = tag!("cvt");
+/// assert_eq!(CVT, Tag::new(b"cvt "));
+/// ```
+/// ```compile_fail
+/// # use hb_subset::*;
+/// const INVALID: Tag = tag!("too long");
+/// ```
+#[macro_export]
+macro_rules! tag {
+    ($tag:literal) => {{
+        const TAG: $crate::Tag = $crate::Tag::parse_or_panic($tag);
+        TAG
+    }};
+}
+
+impl FromStr for Tag {
+    type Err = InvalidTagError;
+
+    /// Parses a tag from a string of one to four characters, e.g. `"GSUB"`. Shorter tags are padded with spaces, so
+    /// both `"cvt"` and `"cvt "` are parsed into the same tag. The tag must be [valid](Tag::is_valid).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Tag {
+    /// Formats the tag as its four characters, including trailing spaces.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.to_be_bytes() {
+            write!(f, "{}", c as char)?;
+        }
+        Ok(())
+    }
 }
 
 impl From<Tag> for u32 {
@@ -70,10 +192,20 @@ impl fmt::Debug for Tag {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tag: [u8; 4] = (*self).into();
+        serializer.serialize_str(&String::from_utf8_lossy(&tag))
+    }
+}
+
 /// Data type for languages.
 ///
 /// Corresponds to a [BCP 47 language tag](https://en.wikipedia.org/wiki/IETF_language_tag).
-#[derive(Clone, Copy)]
+///
+/// HarfBuzz interns languages, so two languages compare equal exactly when their tags are the same.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Language(sys::hb_language_t);
 
 impl Language {
@@ -129,6 +261,119 @@ impl fmt::Display for Language {
     }
 }
 
+impl fmt::Debug for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Language({self})")
+    }
+}
+
+/// Data type for scripts.
+///
+/// Corresponds to an [ISO 15924 script code](https://unicode.org/iso15924/), e.g. `Latn` for Latin or `Cyrl` for
+/// Cyrillic. Each Unicode character belongs to exactly one script, which can be queried with [`Script::of`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Script(sys::hb_script_t);
+
+impl Script {
+    /// Script for characters shared by several scripts, like digits and punctuation.
+    pub const COMMON: Self = Self(sys::hb_script_t_HB_SCRIPT_COMMON);
+    /// Script for combining marks that inherit the script of the preceding character.
+    pub const INHERITED: Self = Self(sys::hb_script_t_HB_SCRIPT_INHERITED);
+    /// Script for unassigned, private-use, non-character and surrogate code points.
+    pub const UNKNOWN: Self = Self(sys::hb_script_t_HB_SCRIPT_UNKNOWN);
+    /// Arabic script.
+    pub const ARABIC: Self = Self(sys::hb_script_t_HB_SCRIPT_ARABIC);
+    /// Armenian script.
+    pub const ARMENIAN: Self = Self(sys::hb_script_t_HB_SCRIPT_ARMENIAN);
+    /// Bengali script.
+    pub const BENGALI: Self = Self(sys::hb_script_t_HB_SCRIPT_BENGALI);
+    /// Cyrillic script.
+    pub const CYRILLIC: Self = Self(sys::hb_script_t_HB_SCRIPT_CYRILLIC);
+    /// Devanagari script.
+    pub const DEVANAGARI: Self = Self(sys::hb_script_t_HB_SCRIPT_DEVANAGARI);
+    /// Georgian script.
+    pub const GEORGIAN: Self = Self(sys::hb_script_t_HB_SCRIPT_GEORGIAN);
+    /// Greek script.
+    pub const GREEK: Self = Self(sys::hb_script_t_HB_SCRIPT_GREEK);
+    /// Han script, i.e. CJK ideographs.
+    pub const HAN: Self = Self(sys::hb_script_t_HB_SCRIPT_HAN);
+    /// Hangul script.
+    pub const HANGUL: Self = Self(sys::hb_script_t_HB_SCRIPT_HANGUL);
+    /// Hebrew script.
+    pub const HEBREW: Self = Self(sys::hb_script_t_HB_SCRIPT_HEBREW);
+    /// Hiragana script.
+    pub const HIRAGANA: Self = Self(sys::hb_script_t_HB_SCRIPT_HIRAGANA);
+    /// Katakana script.
+    pub const KATAKANA: Self = Self(sys::hb_script_t_HB_SCRIPT_KATAKANA);
+    /// Latin script.
+    pub const LATIN: Self = Self(sys::hb_script_t_HB_SCRIPT_LATIN);
+    /// Thai script.
+    pub const THAI: Self = Self(sys::hb_script_t_HB_SCRIPT_THAI);
+
+    /// Constructs a script from its ISO 15924 tag, e.g. `Tag::new(b"Latn")`.
+    ///
+    /// Tags which are valid but not known by HarfBuzz are mapped to [`Script::UNKNOWN`].
+    #[doc(alias = "hb_script_from_iso15924_tag")]
+    pub fn from_iso15924_tag(tag: Tag) -> Self {
+        Self(unsafe { sys::hb_script_from_iso15924_tag(tag.into()) })
+    }
+
+    /// Gets the ISO 15924 tag of the script.
+    #[doc(alias = "hb_script_to_iso15924_tag")]
+    pub fn iso15924_tag(&self) -> Tag {
+        Tag::from(unsafe { sys::hb_script_to_iso15924_tag(self.0) })
+    }
+
+    /// Gets the script the given character belongs to.
+    ///
+    /// Uses the Unicode Character Database compiled into HarfBuzz.
+    #[doc(alias = "hb_unicode_script")]
+    pub fn of(c: char) -> Self {
+        Self(unsafe { sys::hb_unicode_script(sys::hb_unicode_funcs_get_default(), c as u32) })
+    }
+
+    /// Exposes the raw inner value.
+    pub fn as_raw(&self) -> sys::hb_script_t {
+        self.0
+    }
+
+    /// Constructs a script from raw [`sys::hb_script_t`] value.
+    pub fn from_raw(script: sys::hb_script_t) -> Self {
+        Self(script)
+    }
+}
+
+impl FromStr for Script {
+    type Err = InvalidScriptError;
+
+    /// Parses a script from its ISO 15924 tag, e.g. `"Latn"`. The parsing is case-insensitive.
+    #[doc(alias = "hb_script_from_string")]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let script =
+            unsafe { sys::hb_script_from_string(s.as_ptr() as *const c_char, s.len() as i32) };
+        if script == sys::hb_script_t_HB_SCRIPT_INVALID {
+            return Err(InvalidScriptError);
+        }
+        Ok(Self(script))
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag: [u8; 4] = self.iso15924_tag().into();
+        for c in tag {
+            write!(f, "{}", c as char)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Script({self})")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +388,19 @@ mod tests {
         assert_eq!(format!("{:?}", Tag::new(b"DSIG")), "Tag(DSIG)");
     }
 
+    #[test]
+    fn tag_parsing_works() {
+        assert_eq!("GSUB".parse::<Tag>().unwrap(), Tag::new(b"GSUB"));
+        assert_eq!("cvt".parse::<Tag>().unwrap(), Tag::new(b"cvt "));
+        assert_eq!("cvt ".parse::<Tag>().unwrap(), Tag::new(b"cvt "));
+        assert_eq!("OS/2".parse::<Tag>().unwrap().to_string(), "OS/2");
+        assert_eq!(Tag::new(b"cvt ").to_string(), "cvt ");
+        for invalid in ["", "GSUBX", " cvt", "c vt", "\u{e4}", "a\tb"] {
+            assert!(invalid.parse::<Tag>().is_err(), "{invalid:?}");
+        }
+        assert_eq!(tag!("a"), Tag::new(b"a   "));
+    }
+
     #[test]
     fn language_works() {
         assert_eq!(Language::from_str("").unwrap().to_string(), "[invalid]");
@@ -151,5 +409,30 @@ mod tests {
             Language::from_str("non-existent").unwrap().to_string(),
             "non-existent"
         );
+        assert_eq!(
+            Language::from_str("en").unwrap(),
+            Language::from_str("EN").unwrap()
+        );
+        assert_ne!(
+            Language::from_str("en").unwrap(),
+            Language::from_str("fi").unwrap()
+        );
+        assert_eq!(
+            format!("{:?}", Language::from_str("fi").unwrap()),
+            "Language(fi)"
+        );
+    }
+
+    #[test]
+    fn script_works() {
+        assert_eq!(Script::of('a'), Script::LATIN);
+        assert_eq!(Script::of('ж'), Script::CYRILLIC);
+        assert_eq!(Script::of('1'), Script::COMMON);
+        assert_eq!(Script::from_str("Cyrl").unwrap(), Script::CYRILLIC);
+        assert_eq!(Script::from_str("latn").unwrap(), Script::LATIN);
+        assert_eq!(Script::LATIN.to_string(), "Latn");
+        assert_eq!(format!("{:?}", Script::GREEK), "Script(Grek)");
+        assert_eq!(Script::from_iso15924_tag(Tag::new(b"Hebr")), Script::HEBREW);
+        assert!(Script::from_str("").is_err());
     }
 }
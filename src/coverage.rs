@@ -0,0 +1,276 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+};
+
+use crate::{set::CharSet, sys, AllocationError, FontFace, Script, UnicodeBlock};
+
+/// Breakdown of the characters covered by a font face per Unicode script and block.
+///
+/// Only scripts and blocks of which the font covers at least one character are reported. Use
+/// [`FontFace::coverage_report`] to construct one. The [`Display`](fmt::Display) implementation renders the report as
+/// a human-readable table.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let report = font.coverage_report()?;
+/// let cyrillic = report.script(Script::CYRILLIC).unwrap();
+/// println!("Cyrillic: {:.1} %, missing {:?}", cyrillic.percentage(), cyrillic.missing);
+/// println!("{report}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CoverageReport {
+    /// Coverage per script, ordered by the number of covered characters, most covered first.
+    pub scripts: Vec<ScriptCoverage>,
+    /// Coverage per block, ordered by the position of the block in Unicode.
+    pub blocks: Vec<BlockCoverage>,
+}
+
+/// Coverage of a single script. See [`CoverageReport`].
+#[derive(Debug, Clone)]
+pub struct ScriptCoverage {
+    /// The script this coverage refers to.
+    pub script: Script,
+    /// Number of characters of the script covered by the font.
+    pub covered: usize,
+    /// Number of characters in the script.
+    ///
+    /// Only assigned characters are counted, plus any unassigned code points that the font nevertheless maps.
+    pub total: usize,
+    /// Characters of the script which are not covered by the font.
+    pub missing: CharSet<'static>,
+}
+
+/// Coverage of a single Unicode block. See [`CoverageReport`].
+#[derive(Debug, Clone)]
+pub struct BlockCoverage {
+    /// The block this coverage refers to.
+    pub block: UnicodeBlock,
+    /// Number of characters of the block covered by the font.
+    pub covered: usize,
+    /// Number of characters in the block.
+    ///
+    /// Only assigned characters are counted, plus any unassigned code points that the font nevertheless maps.
+    pub total: usize,
+    /// Characters of the block which are not covered by the font.
+    pub missing: CharSet<'static>,
+}
+
+impl ScriptCoverage {
+    /// Returns the percentage of the characters of the script covered by the font.
+    pub fn percentage(&self) -> f64 {
+        percentage(self.covered, self.total)
+    }
+}
+
+impl BlockCoverage {
+    /// Returns the percentage of the characters of the block covered by the font.
+    pub fn percentage(&self) -> f64 {
+        percentage(self.covered, self.total)
+    }
+}
+
+fn percentage(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+impl CoverageReport {
+    /// Computes the coverage report for a font face.
+    ///
+    /// See [`FontFace::coverage_report`].
+    pub fn new(font: &FontFace<'_>) -> Result<Self, AllocationError> {
+        let covered = font.covered_codepoints()?;
+
+        let mut scripts = HashMap::new();
+        let mut blocks = Vec::<BlockCoverage>::new();
+        for c in &covered {
+            if let Some(block) = UnicodeBlock::of(c) {
+                if blocks.last().map(|b| b.block) != Some(block) {
+                    blocks.push(BlockCoverage {
+                        block,
+                        covered: 0,
+                        total: 0,
+                        missing: CharSet::new()?,
+                    });
+                }
+            }
+            if let Entry::Vacant(entry) = scripts.entry(Script::of(c)) {
+                entry.insert(ScriptCoverage {
+                    script: Script::of(c),
+                    covered: 0,
+                    total: 0,
+                    missing: CharSet::new()?,
+                });
+            }
+        }
+
+        // Blocks are small enough to be scanned individually
+        for block in &mut blocks {
+            for c in block.block.range().filter_map(char::from_u32) {
+                if covered.contains(c) {
+                    block.covered += 1;
+                    block.total += 1;
+                } else if is_assigned(c) {
+                    block.total += 1;
+                    block.missing.insert(c);
+                }
+            }
+        }
+
+        // Characters of a script can be anywhere, so everything has to be checked
+        if !scripts.is_empty() {
+            for c in (0..=char::MAX as u32).filter_map(char::from_u32) {
+                let Some(script) = scripts.get_mut(&Script::of(c)) else {
+                    continue;
+                };
+                if covered.contains(c) {
+                    script.covered += 1;
+                    script.total += 1;
+                } else if is_assigned(c) {
+                    script.total += 1;
+                    script.missing.insert(c);
+                }
+            }
+        }
+        let mut scripts = scripts.into_values().collect::<Vec<_>>();
+        scripts.sort_by(|a, b| {
+            b.covered
+                .cmp(&a.covered)
+                .then_with(|| a.script.to_string().cmp(&b.script.to_string()))
+        });
+
+        Ok(Self { scripts, blocks })
+    }
+
+    /// Gets the coverage of the given script, if the font covers any of its characters.
+    pub fn script(&self, script: Script) -> Option<&ScriptCoverage> {
+        self.scripts.iter().find(|s| s.script == script)
+    }
+
+    /// Gets the coverage of the given block, if the font covers any of its characters.
+    pub fn block(&self, block: UnicodeBlock) -> Option<&BlockCoverage> {
+        self.blocks.iter().find(|b| b.block == block)
+    }
+}
+
+/// Tests whether a character has been assigned in the Unicode Character Database compiled into HarfBuzz.
+#[doc(alias = "hb_unicode_general_category")]
+fn is_assigned(c: char) -> bool {
+    let category =
+        unsafe { sys::hb_unicode_general_category(sys::hb_unicode_funcs_get_default(), c as u32) };
+    category != sys::hb_unicode_general_category_t_HB_UNICODE_GENERAL_CATEGORY_UNASSIGNED
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .blocks
+            .iter()
+            .map(|b| b.block.name().len())
+            .max()
+            .unwrap_or(0)
+            .max("Script".len());
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>8} {:>7}",
+            "Script", "Covered", "Total", "%"
+        )?;
+        for script in &self.scripts {
+            writeln!(
+                f,
+                "{:<width$} {:>8} {:>8} {:>6.1}%",
+                script.script.to_string(),
+                script.covered,
+                script.total,
+                script.percentage()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>8} {:>7}",
+            "Block", "Covered", "Total", "%"
+        )?;
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "{:<width$} {:>8} {:>8} {:>6.1}%",
+                block.block.name(),
+                block.covered,
+                block.total,
+                block.percentage()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob};
+
+    #[test]
+    fn report_is_consistent_with_covered_codepoints() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let covered = font.covered_codepoints().unwrap();
+        let report = font.coverage_report().unwrap();
+
+        assert_eq!(
+            report.scripts.iter().map(|s| s.covered).sum::<usize>(),
+            covered.len()
+        );
+        for script in &report.scripts {
+            assert_eq!(script.covered + script.missing.len(), script.total);
+            assert!(script.missing.iter().all(|c| !covered.contains(c)));
+        }
+        for block in &report.blocks {
+            assert_eq!(block.covered + block.missing.len(), block.total);
+            assert!(block.missing.iter().all(|c| block.block.contains(c)));
+        }
+    }
+
+    #[test]
+    fn report_contains_expected_scripts_and_blocks() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let report = font.coverage_report().unwrap();
+
+        assert!(report.script(Script::LATIN).unwrap().covered > 0);
+        assert!(report.script(Script::CYRILLIC).unwrap().covered > 0);
+        assert!(report.script(Script::HAN).is_none());
+
+        let basic_latin = UnicodeBlock::from_name("Basic Latin").unwrap();
+        let basic_latin = report.block(basic_latin).unwrap();
+        assert_eq!(basic_latin.total, 128);
+        assert!(basic_latin.missing.contains('\0'));
+        assert!(!basic_latin.missing.contains('a'));
+
+        let text = report.to_string();
+        assert!(text.contains("Latn"));
+        assert!(text.contains("Basic Latin"));
+    }
+
+    #[test]
+    fn subset_reports_dropped_characters() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = crate::SubsetInput::new().unwrap();
+        subset.unicode_set().insert_range('a'..='z');
+        let font = subset.subset_font(&font).unwrap();
+        let report = font.coverage_report().unwrap();
+
+        assert_eq!(report.scripts.len(), 1);
+        assert_eq!(report.blocks.len(), 1);
+        let latin = report.script(Script::LATIN).unwrap();
+        assert_eq!(latin.covered, 26);
+        assert!(latin.missing.contains('A'));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+/// Embedding licensing rights of a font, as specified by the `fsType` field of the OS/2 table.
+///
+/// Font vendors use these bits to tell what the font may be used for when it is embedded in a document. Subsetting a
+/// font and shipping the subset, e.g. in a PDF or on a web page, is a form of embedding. Use
+/// [`FontFace::embedding_permissions`](crate::FontFace::embedding_permissions) to read the permissions of a font, and
+/// [`EmbeddingPolicy`] to make the subsetter respect them.
+///
+/// See the [OpenType spec](https://learn.microsoft.com/en-us/typography/opentype/spec/os2#fstype) for the exact
+/// meaning of the bits.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let permissions = font.embedding_permissions().unwrap();
+/// assert_eq!(permissions.usage(), EmbeddingUsage::Installable);
+/// assert!(permissions.allows_subsetting());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmbeddingPermissions(u16);
+
+/// Usage permissions of an embedded font. See [`EmbeddingPermissions::usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmbeddingUsage {
+    /// The font may be embedded, and permanently installed on the remote system.
+    Installable,
+    /// The font must not be modified, embedded or exchanged in any manner without first obtaining explicit permission
+    /// of the legal owner.
+    Restricted,
+    /// The font may be embedded, and temporarily loaded on the remote system, but documents using it must be opened
+    /// read-only.
+    PreviewAndPrint,
+    /// The font may be embedded, and temporarily loaded on the remote system. Documents using it may be edited.
+    Editable,
+}
+
+impl EmbeddingPermissions {
+    const RESTRICTED: u16 = 0x0002;
+    const PREVIEW_AND_PRINT: u16 = 0x0004;
+    const EDITABLE: u16 = 0x0008;
+    const NO_SUBSETTING: u16 = 0x0100;
+    const BITMAP_ONLY: u16 = 0x0200;
+
+    /// Constructs permissions from the raw value of the `fsType` field.
+    pub fn from_fs_type(fs_type: u16) -> Self {
+        Self(fs_type)
+    }
+
+    /// Gets the raw value of the `fsType` field.
+    pub fn fs_type(&self) -> u16 {
+        self.0
+    }
+
+    /// Gets the usage permissions of the font.
+    ///
+    /// Old fonts may set several of the usage bits. In that case the least restrictive one applies, as required by the
+    /// OpenType spec.
+    pub fn usage(&self) -> EmbeddingUsage {
+        if self.0 & Self::EDITABLE != 0 {
+            EmbeddingUsage::Editable
+        } else if self.0 & Self::PREVIEW_AND_PRINT != 0 {
+            EmbeddingUsage::PreviewAndPrint
+        } else if self.0 & Self::RESTRICTED != 0 {
+            EmbeddingUsage::Restricted
+        } else {
+            EmbeddingUsage::Installable
+        }
+    }
+
+    /// Tests whether the font uses restricted license embedding. See [`EmbeddingUsage::Restricted`].
+    pub fn is_restricted(&self) -> bool {
+        self.usage() == EmbeddingUsage::Restricted
+    }
+
+    /// Tests whether the font may be embedded only after the font has been temporarily loaded for previewing and
+    /// printing. See [`EmbeddingUsage::PreviewAndPrint`].
+    pub fn is_preview_and_print(&self) -> bool {
+        self.usage() == EmbeddingUsage::PreviewAndPrint
+    }
+
+    /// Tests whether the font may be embedded in editable documents. See [`EmbeddingUsage::Editable`].
+    pub fn is_editable(&self) -> bool {
+        self.usage() == EmbeddingUsage::Editable
+    }
+
+    /// Tests whether the font must not be subsetted prior to embedding.
+    pub fn is_no_subsetting(&self) -> bool {
+        self.0 & Self::NO_SUBSETTING != 0
+    }
+
+    /// Tests whether only bitmaps contained in the font may be embedded, and no outline data.
+    pub fn is_bitmap_only(&self) -> bool {
+        self.0 & Self::BITMAP_ONLY != 0
+    }
+
+    /// Tests whether the font may be subsetted and embedded, i.e. it neither uses restricted license embedding nor
+    /// forbids subsetting.
+    pub fn allows_subsetting(&self) -> bool {
+        !self.is_restricted() && !self.is_no_subsetting()
+    }
+}
+
+impl fmt::Debug for EmbeddingPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddingPermissions")
+            .field("usage", &self.usage())
+            .field("no_subsetting", &self.is_no_subsetting())
+            .field("bitmap_only", &self.is_bitmap_only())
+            .finish()
+    }
+}
+
+/// Policy on how the subsetter treats the embedding permissions of a font. See [`EmbeddingPermissions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EmbeddingPolicy {
+    /// Subset fonts regardless of their embedding permissions.
+    #[default]
+    Ignore,
+    /// Refuse to subset fonts which use restricted license embedding or forbid subsetting.
+    ///
+    /// Fonts without an OS/2 table carry no restrictions, and are subsetted normally.
+    Enforce,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_bits_are_parsed() {
+        let installable = EmbeddingPermissions::from_fs_type(0);
+        assert_eq!(installable.usage(), EmbeddingUsage::Installable);
+        assert!(installable.allows_subsetting());
+
+        let restricted = EmbeddingPermissions::from_fs_type(0x0002);
+        assert!(restricted.is_restricted());
+        assert!(!restricted.allows_subsetting());
+
+        assert!(EmbeddingPermissions::from_fs_type(0x0004).is_preview_and_print());
+        assert!(EmbeddingPermissions::from_fs_type(0x0008).is_editable());
+
+        // The least restrictive usage applies
+        let mixed = EmbeddingPermissions::from_fs_type(0x0002 | 0x0004);
+        assert_eq!(mixed.usage(), EmbeddingUsage::PreviewAndPrint);
+        assert!(mixed.allows_subsetting());
+    }
+
+    #[test]
+    fn flag_bits_are_parsed() {
+        let no_subsetting = EmbeddingPermissions::from_fs_type(0x0108);
+        assert!(no_subsetting.is_editable());
+        assert!(no_subsetting.is_no_subsetting());
+        assert!(!no_subsetting.is_bitmap_only());
+        assert!(!no_subsetting.allows_subsetting());
+
+        let bitmap_only = EmbeddingPermissions::from_fs_type(0x0200);
+        assert!(bitmap_only.is_bitmap_only());
+        assert!(bitmap_only.allows_subsetting());
+        assert_eq!(bitmap_only.fs_type(), 0x0200);
+    }
+}
@@ -1,16 +1,150 @@
 use thiserror::Error;
 
+use crate::{Capability, EmbeddingPermissions, GlyphId, HarfBuzzVersion, Tag, ValidationIssue};
+
 /// An error returned when an allocation fails.
 #[derive(Debug, Error)]
 #[error("Failed to allocate object")]
 pub struct AllocationError;
 
+/// An error returned when a capability is not supported by the linked HarfBuzz. See [`Capability`].
+#[derive(Debug, Error)]
+#[error("{capability} is unsupported by HarfBuzz {}.{}", .version.major, .version.minor)]
+pub struct UnsupportedError {
+    /// The unsupported capability.
+    pub capability: Capability,
+    /// The version of the linked HarfBuzz.
+    pub version: HarfBuzzVersion,
+}
+
 /// An error returned when font face could not be subset.
 #[derive(Debug, Error)]
-#[error("Failed to subset font face")]
-pub struct SubsettingError;
+#[non_exhaustive]
+pub enum SubsettingError {
+    /// HarfBuzz failed to subset the font face.
+    #[error("Failed to subset font face")]
+    Failed,
+    /// The embedding permissions of the font face do not allow subsetting it. See
+    /// [`EmbeddingPolicy`](crate::EmbeddingPolicy).
+    #[error("Embedding permissions of the font face do not allow subsetting")]
+    EmbeddingRestricted(EmbeddingPermissions),
+    /// The produced subset did not pass validation. See
+    /// [`SubsetInput::set_validate_output`](crate::SubsetInput::set_validate_output).
+    #[error("Subset font face failed validation: {}", .0.first().map(ToString::to_string).unwrap_or_default())]
+    InvalidOutput(Vec<ValidationIssue>),
+    /// The subset input uses a capability which the linked HarfBuzz does not support.
+    #[error(transparent)]
+    Unsupported(#[from] UnsupportedError),
+    /// Subsetting needed more memory than the budget allows. The budget in bytes is given. See
+    /// [`SubsetInput::set_memory_budget`](crate::SubsetInput::set_memory_budget).
+    #[error("Subsetting exceeded the memory budget of {0} bytes")]
+    MemoryBudgetExceeded(usize),
+}
+
+/// An error returned when a [`SubsetInputBuilder`](crate::SubsetInputBuilder) could not construct a subset input.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SubsetInputError {
+    /// The subset input could not be allocated.
+    #[error(transparent)]
+    Allocation(#[from] AllocationError),
+    /// The subset input uses a capability which the linked HarfBuzz does not support.
+    #[error(transparent)]
+    Unsupported(#[from] UnsupportedError),
+    /// A variation axis was pinned to a location which is not a finite number.
+    #[error("Invalid location {value} for axis {axis:?}")]
+    InvalidAxisLocation {
+        /// The pinned axis.
+        axis: Tag,
+        /// The invalid location.
+        value: f32,
+    },
+    /// A variation axis was pinned to several different locations.
+    #[error("Axis {0:?} is pinned to conflicting locations")]
+    ConflictingAxisLocations(Tag),
+    /// A glyph id is not valid in any font.
+    #[error("Invalid glyph id {0}")]
+    InvalidGlyph(GlyphId),
+    /// A name table record was overridden with a value on a platform other than Macintosh (1) or Windows (3), for
+    /// which HarfBuzz cannot encode values.
+    #[error("Name table values cannot be encoded for platform {0}")]
+    UnsupportedNamePlatform(u16),
+    /// A name table value is too long to be passed to HarfBuzz.
+    #[error("Name table value of {0} bytes is too long")]
+    NameValueTooLong(usize),
+}
 
 /// An error returned when a font face could not be extracted from blob.
 #[derive(Debug, Error)]
 #[error("Failed to extract font face from blob")]
 pub struct FontFaceExtractionError;
+
+/// An error returned when a [`SubsetReport`](crate::SubsetReport) could not be constructed.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SubsetReportError {
+    /// The sets needed for counting codepoints could not be allocated.
+    #[error(transparent)]
+    Allocation(#[from] AllocationError),
+    /// The serialized subset is not a valid font file.
+    #[error(transparent)]
+    InvalidSubset(#[from] FontFaceExtractionError),
+    /// Executing the subset plan failed.
+    #[error(transparent)]
+    Subsetting(#[from] SubsettingError),
+}
+
+/// An error returned when a string could not be parsed into a [`Script`](crate::Script).
+#[derive(Debug, Error)]
+#[error("Invalid script tag")]
+pub struct InvalidScriptError;
+
+/// An error returned when a string could not be parsed into a [`Tag`](crate::Tag).
+#[derive(Debug, Error)]
+#[error("Invalid tag")]
+pub struct InvalidTagError;
+
+/// An error returned when a string does not name any known [`UnicodeBlock`](crate::UnicodeBlock).
+#[derive(Debug, Error)]
+#[error("Unknown Unicode block")]
+pub struct UnknownUnicodeBlockError;
+
+/// An error returned when a font could not be encoded as WOFF2.
+#[derive(Debug, Error)]
+#[error("Failed to encode font as WOFF2")]
+pub struct Woff2EncodingError;
+
+/// An error returned when HarfBuzz allocator hooks could not be installed, as HarfBuzz already uses other ones. See
+/// [`allocator::set_allocator_hooks`](crate::allocator::set_allocator_hooks).
+#[cfg(hb_subset_rust_allocator)]
+#[derive(Debug, Error)]
+#[error("HarfBuzz allocator hooks are already in use")]
+pub struct AllocatorHooksError;
+
+/// An error returned when a font could not be subset in a worker process. See
+/// [`isolated::IsolatedSubsetter`](crate::isolated::IsolatedSubsetter).
+#[cfg(all(feature = "isolated", unix))]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum IsolatedSubsettingError {
+    /// The worker process could not be started, or communicating with it failed.
+    #[error("Failed to communicate with the worker process")]
+    Io(#[from] std::io::Error),
+    /// The worker did not finish within the timeout, and it was killed. The timeout is given.
+    #[error("Subsetting did not finish within {0:?}")]
+    TimedOut(std::time::Duration),
+    /// The worker exited without producing a response, e.g. because it crashed or hit a resource limit.
+    #[error("The worker process exited unexpectedly ({0})")]
+    Crashed(std::process::ExitStatus),
+    /// The worker produced a response which could not be understood, e.g. because it is not a subsetting worker.
+    #[error("The worker process sent an invalid response")]
+    InvalidResponse,
+    /// The worker failed to subset the font, e.g. because subsetting exceeded the memory budget of the input. The
+    /// error is rebuilt from the response of the worker.
+    #[error(transparent)]
+    Subsetting(#[from] SubsettingError),
+    /// The worker failed for another reason, e.g. because the font could not be loaded. The error message reported by
+    /// the worker is given.
+    #[error("{0}")]
+    Failed(String),
+}
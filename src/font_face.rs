@@ -1,7 +1,8 @@
-use std::{ffi::c_char, marker::PhantomData, ops::Deref, ptr::null_mut};
+use std::{marker::PhantomData, ops::Deref, ptr::null_mut};
 
 use crate::{
-    map::Map, set::CharSet, sys, AllocationError, Blob, FontFaceExtractionError, Language,
+    map::Map, name_table, set::CharSet, sys, AllocationError, Blob, CoverageReport,
+    EmbeddingPermissions, FontFaceExtractionError, GlyphId, Language, NameId, Tag,
 };
 
 /// A font face is an object that represents a single face from within a font family.
@@ -46,6 +47,49 @@ impl<'a> FontFace<'a> {
         unsafe { Blob::from_raw(sys::hb_face_reference_blob(self.as_raw())) }
     }
 
+    /// Fetches the tags of all tables in the face.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// assert!(font.table_tags().contains(&Tag::new(b"glyf")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_face_get_table_tags")]
+    pub fn table_tags(&self) -> Vec<Tag> {
+        let count =
+            unsafe { sys::hb_face_get_table_tags(self.as_raw(), 0, null_mut(), null_mut()) };
+        let mut len = count;
+        let mut tags = vec![0; count as usize];
+        unsafe {
+            sys::hb_face_get_table_tags(self.as_raw(), 0, &mut len as *mut u32, tags.as_mut_ptr())
+        };
+        tags.truncate(len as usize);
+        tags.into_iter().map(Tag::from).collect()
+    }
+
+    /// Fetches the raw data of a table in the face.
+    ///
+    /// Returns an empty blob if the face does not contain the table.
+    #[doc(alias = "hb_face_reference_table")]
+    pub fn table(&self, tag: Tag) -> Blob<'_> {
+        unsafe { Blob::from_raw(sys::hb_face_reference_table(self.as_raw(), tag.into())) }
+    }
+
+    /// Gets the embedding permissions of the face from the `fsType` field of its OS/2 table.
+    ///
+    /// Returns [`None`] if the face has no OS/2 table.
+    pub fn embedding_permissions(&self) -> Option<EmbeddingPermissions> {
+        let os2 = self.table(Tag::new(b"OS/2"));
+        let fs_type = os2.get(8..10)?;
+        Some(EmbeddingPermissions::from_fs_type(u16::from_be_bytes([
+            fs_type[0], fs_type[1],
+        ])))
+    }
+
     /// Fetches the glyph-count value of the specified face object.
     #[doc(alias = "hb_face_get_glyph_count")]
     pub fn glyph_count(&self) -> usize {
@@ -53,17 +97,34 @@ impl<'a> FontFace<'a> {
     }
 
     /// Collects all of the Unicode characters covered by the font face.
+    ///
+    /// The set is always empty when the bundled HarfBuzz is built with feature `bundled-lean` or `bundled-mini`.
     #[doc(alias = "hb_face_collect_unicodes")]
     pub fn covered_codepoints(&self) -> Result<CharSet, AllocationError> {
         let set = CharSet::new()?;
-        unsafe { sys::hb_face_collect_unicodes(self.as_raw(), set.as_raw()) };
+        #[cfg(not(hb_subset_lean))]
+        unsafe {
+            sys::hb_face_collect_unicodes(self.as_raw(), set.as_raw())
+        };
         Ok(set)
     }
 
+    /// Breaks the covered codepoints down by Unicode script and block.
+    ///
+    /// For each script and block the font has characters from, the report tells how many characters are covered, how
+    /// large portion of the script or block that is, and which characters are missing. This is useful e.g. for picking
+    /// fallback fonts, or checking what a subset actually dropped. See [`CoverageReport`] for details.
+    pub fn coverage_report(&self) -> Result<CoverageReport, AllocationError> {
+        CoverageReport::new(self)
+    }
+
     /// Collects the mapping from Unicode characters to nominal glyphs of the face.
+    ///
+    /// The map is always empty when the bundled HarfBuzz is built with feature `bundled-lean` or `bundled-mini`.
     #[doc(alias = "hb_face_collect_nominal_glyph_mapping")]
-    pub fn nominal_glyph_mapping(&self) -> Result<Map<'static, char, u32>, AllocationError> {
+    pub fn nominal_glyph_mapping(&self) -> Result<Map<'static, char, GlyphId>, AllocationError> {
         let map = Map::new()?;
+        #[cfg(not(hb_subset_lean))]
         unsafe {
             sys::hb_face_collect_nominal_glyph_mapping(self.as_raw(), map.as_raw(), null_mut())
         };
@@ -133,10 +194,25 @@ impl<'a> FontFace<'a> {
     /// string directly.
     ///
     /// If `language` is `null()`, English is assumed.
+    ///
+    /// The name is always empty when the bundled HarfBuzz is built with feature `bundled-lean` or `bundled-mini`, as
+    /// is the value of each convenience method. [`Self::ot_names`] reads the name table itself, and works with all
+    /// builds.
     #[doc(alias = "hb_ot_name_get_utf8")]
     #[doc(alias = "hb_ot_name_get_utf16")]
     #[doc(alias = "hb_ot_name_get_utf32")]
-    pub fn ot_name(&self, name: impl Into<sys::hb_ot_name_id_t>, language: Language) -> String {
+    pub fn ot_name(&self, name: impl Into<NameId>, language: Language) -> String {
+        #[cfg(hb_subset_lean)]
+        {
+            let _ = (name.into(), language);
+            String::new()
+        }
+        #[cfg(not(hb_subset_lean))]
+        self.lookup_ot_name(name.into(), language)
+    }
+
+    #[cfg(not(hb_subset_lean))]
+    fn lookup_ot_name(&self, name: NameId, language: Language) -> String {
         let name = name.into();
         let mut len = unsafe {
             sys::hb_ot_name_get_utf8(
@@ -155,7 +231,7 @@ impl<'a> FontFace<'a> {
                 name,
                 language.as_raw(),
                 &mut len as *mut u32,
-                buf.as_mut_ptr() as *mut c_char,
+                buf.as_mut_ptr().cast(),
             )
         };
         assert!(len <= full_len);
@@ -164,6 +240,40 @@ impl<'a> FontFace<'a> {
         String::from_utf8(buf).expect("Output is promised to be valid UTF-8")
     }
 
+    /// Lists all records of the OpenType name table together with their values.
+    ///
+    /// Unlike [`Self::ot_name`], which picks the best matching record, this lists every record of every platform and
+    /// language with its own value. This can be used e.g. for checking which names a subset retains. Entries are
+    /// ordered by name id, and then by their order in the table. Entries keep the platform, encoding and language ids
+    /// of their records, so records whose language is not known can still be told apart.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// for entry in font.ot_names() {
+    ///     println!("{:?} ({}): {}", entry.name_id, entry.language, entry.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ot_names(&self) -> Vec<NameEntry> {
+        let mut entries = name_table::records(&self.table(Tag::new(b"name")))
+            .into_iter()
+            .map(|record| NameEntry {
+                platform_id: record.platform_id,
+                encoding_id: record.encoding_id,
+                language_id: record.language_id,
+                name_id: record.name_id.into(),
+                language: record.language,
+                value: record.value,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.name_id);
+        entries
+    }
+
     /// Gets copyright notice.
     ///
     /// # Example
@@ -177,10 +287,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_COPYRIGHT")]
     pub fn copyright(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::COPYRIGHT,
-            Language::default(),
-        )
+        self.ot_name(NameId::COPYRIGHT, Language::default())
     }
 
     /// Gets font family name.
@@ -196,10 +303,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_FONT_FAMILY")]
     pub fn font_family(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::FONT_FAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::FONT_FAMILY, Language::default())
     }
 
     /// Gets font subfamily name.
@@ -215,10 +319,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_FONT_SUBFAMILY")]
     pub fn font_subfamily(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::FONT_SUBFAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::FONT_SUBFAMILY, Language::default())
     }
 
     /// Gets unique font identifier.
@@ -234,10 +335,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_UNIQUE_ID")]
     pub fn unique_id(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::UNIQUE_ID,
-            Language::default(),
-        )
+        self.ot_name(NameId::UNIQUE_ID, Language::default())
     }
 
     /// Gets full font name that reflects all family and relevant subfamily descriptors.
@@ -253,10 +351,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_FULL_NAME")]
     pub fn full_name(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::FULL_NAME,
-            Language::default(),
-        )
+        self.ot_name(NameId::FULL_NAME, Language::default())
     }
 
     /// Gets version string.
@@ -272,10 +367,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_VERSION_STRING")]
     pub fn version_string(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::VERSION_STRING,
-            Language::default(),
-        )
+        self.ot_name(NameId::VERSION_STRING, Language::default())
     }
 
     /// Gets PostScript name for the font.
@@ -291,10 +383,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_POSTSCRIPT_NAME")]
     pub fn postscript_name(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::POSTSCRIPT_NAME,
-            Language::default(),
-        )
+        self.ot_name(NameId::POSTSCRIPT_NAME, Language::default())
     }
 
     /// Gets trademark information.
@@ -310,10 +399,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_TRADEMARK")]
     pub fn trademark(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::TRADEMARK,
-            Language::default(),
-        )
+        self.ot_name(NameId::TRADEMARK, Language::default())
     }
 
     /// Gets manufacturer name.
@@ -329,10 +415,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_MANUFACTURER")]
     pub fn manufacturer(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::MANUFACTURER,
-            Language::default(),
-        )
+        self.ot_name(NameId::MANUFACTURER, Language::default())
     }
 
     /// Gets designer name.
@@ -348,10 +431,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_DESIGNER")]
     pub fn designer(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::DESIGNER,
-            Language::default(),
-        )
+        self.ot_name(NameId::DESIGNER, Language::default())
     }
 
     /// Gets description.
@@ -367,10 +447,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_DESCRIPTION")]
     pub fn description(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::DESCRIPTION,
-            Language::default(),
-        )
+        self.ot_name(NameId::DESCRIPTION, Language::default())
     }
 
     /// Gets URL of font vendor.
@@ -386,10 +463,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_VENDOR_URL")]
     pub fn vendor_url(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::VENDOR_URL,
-            Language::default(),
-        )
+        self.ot_name(NameId::VENDOR_URL, Language::default())
     }
 
     /// Gets URL of typeface designer.
@@ -405,10 +479,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_DESIGNER_URL")]
     pub fn designer_url(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::DESIGNER_URL,
-            Language::default(),
-        )
+        self.ot_name(NameId::DESIGNER_URL, Language::default())
     }
 
     /// Gets license description.
@@ -424,10 +495,7 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_LICENSE")]
     pub fn license(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::LICENSE,
-            Language::default(),
-        )
+        self.ot_name(NameId::LICENSE, Language::default())
     }
 
     /// Gets URL where additional licensing information can be found.
@@ -443,103 +511,87 @@ impl<'a> FontFace<'a> {
     /// ```
     #[doc(alias = "HB_OT_NAME_ID_LICENSE_URL")]
     pub fn license_url(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::LICENSE_URL,
-            Language::default(),
-        )
+        self.ot_name(NameId::LICENSE_URL, Language::default())
     }
 
     /// Gets typographic family name.
     #[doc(alias = "HB_OT_NAME_ID_TYPOGRAPHIC_FAMILY")]
     pub fn typographic_family(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::TYPOGRAPHIC_FAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::TYPOGRAPHIC_FAMILY, Language::default())
     }
 
     /// Gets typographic subfamily name.
     #[doc(alias = "HB_OT_NAME_ID_TYPOGRAPHIC_SUBFAMILY")]
     pub fn typographic_subfamily(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::TYPOGRAPHIC_SUBFAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::TYPOGRAPHIC_SUBFAMILY, Language::default())
     }
 
     /// Gets compatible full name for MacOS.
     #[doc(alias = "HB_OT_NAME_ID_MAC_FULL_NAME")]
     pub fn mac_full_name(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::MAC_FULL_NAME,
-            Language::default(),
-        )
+        self.ot_name(NameId::MAC_FULL_NAME, Language::default())
     }
 
     /// Gets sample text.
     #[doc(alias = "HB_OT_NAME_ID_SAMPLE_TEXT")]
     pub fn sample_text(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::SAMPLE_TEXT,
-            Language::default(),
-        )
+        self.ot_name(NameId::SAMPLE_TEXT, Language::default())
     }
 
     /// Gets PostScript CID findfont name.
     #[doc(alias = "HB_OT_NAME_ID_CID_FINDFONT_NAME")]
     pub fn cid_findfont_name(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::CID_FINDFONT_NAME,
-            Language::default(),
-        )
+        self.ot_name(NameId::CID_FINDFONT_NAME, Language::default())
     }
 
     /// Gets WWS family Name.
     #[doc(alias = "HB_OT_NAME_ID_WWS_FAMILY")]
     pub fn wws_family(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::WWS_FAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::WWS_FAMILY, Language::default())
     }
 
     /// Gets WWS subfamily Name.
     #[doc(alias = "HB_OT_NAME_ID_WWS_SUBFAMILY")]
     pub fn wws_subfamily(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::WWS_SUBFAMILY,
-            Language::default(),
-        )
+        self.ot_name(NameId::WWS_SUBFAMILY, Language::default())
     }
 
     /// Gets light background palette.
     #[doc(alias = "HB_OT_NAME_ID_LIGHT_BACKGROUND")]
     pub fn light_background(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::LIGHT_BACKGROUND,
-            Language::default(),
-        )
+        self.ot_name(NameId::LIGHT_BACKGROUND, Language::default())
     }
 
     /// Gets dark background palette.
     #[doc(alias = "HB_OT_NAME_ID_DARK_BACKGROUND")]
     pub fn dark_background(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::DARK_BACKGROUND,
-            Language::default(),
-        )
+        self.ot_name(NameId::DARK_BACKGROUND, Language::default())
     }
 
     /// Gets variations PostScript name prefix.
     #[doc(alias = "HB_OT_NAME_ID_VARIATIONS_PS_PREFIX")]
     pub fn variations_ps_prefix(&self) -> String {
-        self.ot_name(
-            sys::hb_ot_name_id_predefined_t::VARIATIONS_PS_PREFIX,
-            Language::default(),
-        )
+        self.ot_name(NameId::VARIATIONS_PS_PREFIX, Language::default())
     }
 }
 
+/// A single entry of the OpenType name table. See [`FontFace::ot_names`].
+#[derive(Debug, Clone)]
+pub struct NameEntry {
+    /// Platform id of the record, e.g. 1 for Macintosh and 3 for Windows.
+    pub platform_id: u16,
+    /// Platform-specific encoding id of the record.
+    pub encoding_id: u16,
+    /// Platform-specific language id of the record. Ids from `0x8000` refer to the language tags of the table.
+    pub language_id: u16,
+    /// Name id of the entry.
+    pub name_id: NameId,
+    /// Language of the entry. This is the invalid language if the language id of the record is not known.
+    pub language: Language,
+    /// Value of the entry.
+    pub value: String,
+}
+
 impl<'a> Drop for FontFace<'a> {
     #[doc(alias = "hb_face_destroy")]
     fn drop(&mut self) {
@@ -570,6 +622,8 @@ impl<'a> Drop for PreprocessedFontFace<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
     use crate::tests::NOTO_SANS;
 
@@ -591,14 +645,91 @@ mod tests {
     fn nominal_glyph_mapping_works() {
         let font_face = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
         let map = font_face.nominal_glyph_mapping().unwrap();
-        assert_eq!(map.get('a').unwrap(), 68);
-        assert_eq!(map.get('b').unwrap(), 69);
-        assert_eq!(map.get('c').unwrap(), 70);
-        assert_eq!(map.get('d').unwrap(), 71);
-        assert_eq!(map.get('e').unwrap(), 72);
-        assert_eq!(map.get('f').unwrap(), 73);
-        assert_eq!(map.get('i').unwrap(), 76);
-        assert_eq!(map.get('ﬃ').unwrap(), 1656);
+        assert_eq!(map.get('a').unwrap(), GlyphId::new(68));
+        assert_eq!(map.get('b').unwrap(), GlyphId::new(69));
+        assert_eq!(map.get('c').unwrap(), GlyphId::new(70));
+        assert_eq!(map.get('d').unwrap(), GlyphId::new(71));
+        assert_eq!(map.get('e').unwrap(), GlyphId::new(72));
+        assert_eq!(map.get('f').unwrap(), GlyphId::new(73));
+        assert_eq!(map.get('i').unwrap(), GlyphId::new(76));
+        assert_eq!(map.get('ﬃ').unwrap(), GlyphId::new(1656));
+    }
+
+    #[test]
+    fn ot_names_lists_name_table() {
+        let font_face = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let names = font_face.ot_names();
+        let en = Language::from_str("en").unwrap();
+        let family = names
+            .iter()
+            .find(|entry| entry.name_id == NameId::FONT_FAMILY)
+            .unwrap();
+        assert_eq!(family.language, en);
+        assert_eq!(family.value, "Noto Sans");
+        assert!(names
+            .iter()
+            .any(|entry| entry.name_id == NameId::MANUFACTURER && !entry.value.is_empty()));
+        assert!(names.windows(2).all(|w| w[0].name_id <= w[1].name_id));
+
+        // By default, only name ids 0-6 are retained
+        let mut subset = crate::SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        let subset = subset.subset_font(&font_face).unwrap();
+        let names = subset.ot_names();
+        assert!(!names.is_empty());
+        assert!(names
+            .iter()
+            .all(|entry| entry.name_id <= NameId::POSTSCRIPT_NAME));
+    }
+
+    #[test]
+    fn ot_names_lists_each_record_with_its_own_value() {
+        let name = crate::name_table::tests::name_table(
+            &[
+                (3, 1, 0x0409, 1, "Family"),
+                (3, 1, 0x0407, 1, "Familie"),
+                (3, 1, 0x7777, 1, "Vendor leftover"),
+            ],
+            &[],
+        );
+        // A font with only a name table
+        let mut font = Vec::new();
+        for value in [0x0001_0000u32.to_be_bytes(), [0, 1, 0, 16]] {
+            font.extend(value);
+        }
+        font.extend([0; 4]);
+        font.extend(b"name");
+        for value in [0, 28, name.len() as u32] {
+            font.extend(value.to_be_bytes());
+        }
+        font.extend(&name);
+
+        let font_face = FontFace::new(Blob::from_bytes(&font).unwrap()).unwrap();
+        let names = font_face
+            .ot_names()
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.platform_id,
+                    entry.language_id,
+                    entry.language.to_string(),
+                    entry.value,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                (3, 0x0409, "en".to_string(), "Family".to_string()),
+                (3, 0x0407, "de".to_string(), "Familie".to_string()),
+                (
+                    3,
+                    0x7777,
+                    "[invalid]".to_string(),
+                    "Vendor leftover".to_string()
+                ),
+            ]
+        );
     }
 
     #[test]
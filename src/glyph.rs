@@ -0,0 +1,129 @@
+use std::fmt;
+
+/// Defines a newtype over a glyph index.
+macro_rules! glyph_id {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(u32);
+
+        impl $name {
+            /// Constructs a glyph id from its raw value.
+            pub const fn new(id: u32) -> Self {
+                Self(id)
+            }
+
+            /// Gets the raw value of the glyph id.
+            pub const fn to_u32(self) -> u32 {
+                self.0
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(id: u32) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($name), "({})"), self.0)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+glyph_id! {
+    /// Index of a glyph in a font face.
+    ///
+    /// Glyph ids are what character-to-glyph mappings point to, and what [`SubsetInput::glyph_set`] is made of. They
+    /// are only meaningful together with the font face they were obtained from.
+    ///
+    /// [`SubsetInput::glyph_set`]: crate::SubsetInput::glyph_set
+    GlyphId
+}
+
+glyph_id! {
+    /// Index of a glyph in the original font face of a subsetting operation.
+    ///
+    /// Mappings between the original font face and its subset use [`OldGlyphId`] and [`NewGlyphId`], so that glyph ids
+    /// of the two fonts cannot be mixed up by accident. A [`GlyphId`] of the original font can be converted into an
+    /// [`OldGlyphId`] and back with [`From`].
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let a = font.nominal_glyph_mapping()?.get('a').unwrap();
+    ///
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// let plan = subset.plan(&font)?;
+    /// let new_a: NewGlyphId = plan.old_to_new_glyph_mapping().get(a.into()).unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    OldGlyphId
+}
+
+glyph_id! {
+    /// Index of a glyph in the subset font face produced by a subsetting operation.
+    ///
+    /// See [`OldGlyphId`]. A [`GlyphId`] of the subset font can be converted into a [`NewGlyphId`] and back with
+    /// [`From`].
+    NewGlyphId
+}
+
+impl From<GlyphId> for OldGlyphId {
+    fn from(id: GlyphId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<OldGlyphId> for GlyphId {
+    fn from(id: OldGlyphId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<GlyphId> for NewGlyphId {
+    fn from(id: GlyphId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<NewGlyphId> for GlyphId {
+    fn from(id: NewGlyphId) -> Self {
+        Self(id.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_ids_convert() {
+        let glyph = GlyphId::new(5);
+        assert_eq!(u32::from(glyph), 5);
+        assert_eq!(glyph.to_u32(), 5);
+        assert_eq!(OldGlyphId::from(glyph), OldGlyphId::new(5));
+        assert_eq!(GlyphId::from(NewGlyphId::new(7)), GlyphId::new(7));
+        assert_eq!(format!("{glyph:?}"), "GlyphId(5)");
+        assert_eq!(format!("{:?}", NewGlyphId::new(5)), "NewGlyphId(5)");
+        assert_eq!(glyph.to_string(), "5");
+    }
+}
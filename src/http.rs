@@ -0,0 +1,498 @@
+//! HTTP handler serving font subsets on request.
+//!
+//! [`SubsetService`] turns requests such as `GET /font?text=Hello` into subsets of a single font. The handler is built
+//! on the types of the [`http`] crate, so it can be plugged into any server framework which uses them, e.g. hyper or
+//! axum.
+//!
+//! The following query parameters are supported:
+//! - `text`: Characters to include in the subset. The value is percent-decoded.
+//! - `unicode-range`: Comma-separated list of codepoint ranges to include in the subset, using the syntax of the CSS
+//!   `unicode-range` descriptor, e.g. `U+0-7F,U+20AC,U+4??`.
+//! - `format`: Either `woff2` (the default) or `ttf`. The latter returns the font in its original sfnt flavor, so
+//!   fonts with CFF outlines are served as `font/otf`.
+//!
+//! At least one of `text` and `unicode-range` must be given. If both are given, the subset covers the union of them.
+//!
+//! Responses carry an entity tag derived from the font and the subset, and are marked cacheable for a long time, as
+//! the same request always produces the same font.
+
+use std::{sync::Arc, time::Duration};
+
+use http::{
+    header::{
+        ACCESS_CONTROL_ALLOW_ORIGIN, ALLOW, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG,
+        IF_NONE_MATCH,
+    },
+    HeaderValue, Method, Request, Response, StatusCode,
+};
+
+use crate::{
+    cache::{CacheKey, FontHash, SubsetCache},
+    woff2, FontFace, PreprocessedFontFace, SubsetInput,
+};
+
+/// Number of subsets kept in memory by default.
+const DEFAULT_CACHE_CAPACITY: usize = 256;
+/// How long clients may cache responses by default.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Service serving subsets of a single font over HTTP.
+///
+/// The font is preprocessed once when the service is constructed, and produced subsets are kept in a [`SubsetCache`].
+/// The service can be shared between threads, so a single instance can serve all requests. On WebAssembly, where the
+/// bundled HarfBuzz is built without thread safety, the service is neither [`Send`] nor [`Sync`].
+///
+/// # Example
+/// ```
+/// # use hb_subset::{*, http::SubsetService};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let service = SubsetService::new(&font);
+///
+/// let request = ::http::Request::get("/font?text=Hello&format=woff2").body(())?;
+/// let response = service.handle(&request);
+/// assert_eq!(response.status(), ::http::StatusCode::OK);
+/// assert_eq!(response.headers()["content-type"], "font/woff2");
+/// # Ok(())
+/// # }
+/// ```
+pub struct SubsetService<'a> {
+    font: PreprocessedFontFace<'a>,
+    hash: FontHash,
+    cache: SubsetCache,
+    max_age: Duration,
+}
+
+// SAFETY: HarfBuzz objects are safe to use from multiple threads as long as they are not mutated. The service never
+// mutates the font face after preprocessing it, and subsetting only reads from the face. On WebAssembly, HarfBuzz is
+// built without thread safety (`HB_NO_MT`), so the service is not shared there.
+#[cfg(not(target_family = "wasm"))]
+unsafe impl Send for SubsetService<'_> {}
+#[cfg(not(target_family = "wasm"))]
+unsafe impl Sync for SubsetService<'_> {}
+
+impl<'a> SubsetService<'a> {
+    /// Creates a new service serving subsets of the given font.
+    pub fn new(font: &FontFace<'a>) -> Self {
+        Self {
+            hash: FontHash::new(font),
+            font: font.preprocess_for_subsetting(),
+            cache: SubsetCache::new(DEFAULT_CACHE_CAPACITY),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    /// Uses the given cache for storing produced subsets.
+    ///
+    /// By default, the service keeps up to 256 subsets in memory.
+    pub fn with_cache(mut self, cache: SubsetCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Sets how long clients may cache the responses. Defaults to one year.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Handles a single request.
+    ///
+    /// Only the method, the query string and the `If-None-Match` header of the request are inspected. The path is
+    /// ignored, so the service can be mounted at any route.
+    pub fn handle<B>(&self, request: &Request<B>) -> Response<Vec<u8>> {
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            let mut response = error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+            response
+                .headers_mut()
+                .insert(ALLOW, HeaderValue::from_static("GET, HEAD"));
+            return response;
+        }
+
+        let query = match Query::parse(request.uri().query().unwrap_or("")) {
+            Ok(query) => query,
+            Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+        };
+        let Ok(mut input) = SubsetInput::new() else {
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to allocate");
+        };
+        let mut unicode_set = input.unicode_set();
+        for c in query.text.chars() {
+            unicode_set.insert(c);
+        }
+        for (first, last) in query.ranges {
+            unicode_set.insert_range(first..=last);
+        }
+
+        let key = match query.format {
+            Format::Sfnt => CacheKey::new(&self.hash, &input),
+            Format::Woff2 => CacheKey::new(&self.hash, &input).variant("woff2"),
+        };
+        let etag = HeaderValue::try_from(format!("\"{key}\"")).unwrap();
+        let cache_control = HeaderValue::try_from(format!(
+            "public, max-age={}, immutable",
+            self.max_age.as_secs()
+        ))
+        .unwrap();
+        if let Some(if_none_match) = request.headers().get(IF_NONE_MATCH) {
+            if etag_matches(if_none_match, &etag) {
+                let mut response = Response::new(Vec::new());
+                *response.status_mut() = StatusCode::NOT_MODIFIED;
+                let headers = response.headers_mut();
+                headers.insert(ETAG, etag);
+                headers.insert(CACHE_CONTROL, cache_control);
+                headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+                return response;
+            }
+        }
+
+        let (data, content_type) = match self.produce(&input, query.format, key) {
+            Ok(result) => result,
+            Err(message) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, message),
+        };
+
+        let body = if request.method() == Method::HEAD {
+            Vec::new()
+        } else {
+            data.to_vec()
+        };
+        let mut response = Response::new(body);
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(data.len()));
+        headers.insert(ETAG, etag);
+        headers.insert(CACHE_CONTROL, cache_control);
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        response
+    }
+}
+
+impl SubsetService<'_> {
+    /// Produces the subset font in the requested format, returning its data and media type.
+    fn produce(
+        &self,
+        input: &SubsetInput,
+        format: Format,
+        key: CacheKey,
+    ) -> Result<(Arc<[u8]>, &'static str), &'static str> {
+        if format == Format::Woff2 {
+            if let Some(data) = self.cache.get(&key) {
+                return Ok((data, "font/woff2"));
+            }
+        }
+
+        let font = self
+            .cache
+            .subset_with_hash(&self.font, &self.hash, input)
+            .map_err(|_| "Failed to subset font")?;
+        Ok(match format {
+            Format::Sfnt if font.starts_with(b"OTTO") => (font, "font/otf"),
+            Format::Sfnt => (font, "font/ttf"),
+            Format::Woff2 => {
+                let data: Arc<[u8]> = woff2::encode(&font)
+                    .map_err(|_| "Failed to encode font")?
+                    .into();
+                self.cache.insert(key, data.clone());
+                (data, "font/woff2")
+            }
+        })
+    }
+}
+
+/// Format of the served font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Plain OpenType font, as produced by the subsetter.
+    Sfnt,
+    /// WOFF2 compressed font.
+    Woff2,
+}
+
+/// Parsed query string of a subset request.
+#[derive(Debug)]
+struct Query {
+    text: String,
+    ranges: Vec<(char, char)>,
+    format: Format,
+}
+
+impl Query {
+    fn parse(query: &str) -> Result<Self, &'static str> {
+        let mut text = None;
+        let mut ranges = None;
+        let mut format = Format::Woff2;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value).ok_or("Invalid percent-encoding in query")?;
+            match name {
+                "text" => text = Some(value),
+                "unicode-range" => ranges = Some(parse_unicode_range(&value)?),
+                "format" => {
+                    format = match value.as_str() {
+                        "woff2" => Format::Woff2,
+                        "ttf" | "otf" => Format::Sfnt,
+                        _ => return Err("Unsupported format"),
+                    }
+                }
+                _ => {}
+            }
+        }
+        if text.is_none() && ranges.is_none() {
+            return Err("Either text or unicode-range must be given");
+        }
+        Ok(Self {
+            text: text.unwrap_or_default(),
+            ranges: ranges.unwrap_or_default(),
+            format,
+        })
+    }
+}
+
+/// Decodes a percent-encoded query string value. Plus signs are decoded as spaces.
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let high = (iter.next()? as char).to_digit(16)?;
+                let low = (iter.next()? as char).to_digit(16)?;
+                bytes.push((high * 16 + low) as u8);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Parses a list of ranges in the syntax of the CSS `unicode-range` descriptor.
+///
+/// Surrogate code points are not characters, so they are left out of the returned ranges.
+fn parse_unicode_range(value: &str) -> Result<Vec<(char, char)>, &'static str> {
+    const INVALID: &str = "Invalid unicode-range";
+
+    let mut ranges = Vec::new();
+    for range in value.split(',') {
+        let range = range.trim();
+        let range = range
+            .strip_prefix("U+")
+            .or_else(|| range.strip_prefix("u+"))
+            .ok_or(INVALID)?;
+        let (first, last) = match range.split_once('-') {
+            Some((first, last)) => (parse_hex(first)?, parse_hex(last)?),
+            None if range.contains('?') => {
+                let digits = range.trim_end_matches('?');
+                if digits.contains('?') {
+                    return Err(INVALID);
+                }
+                (
+                    parse_hex(&range.replace('?', "0"))?,
+                    parse_hex(&range.replace('?', "F"))?,
+                )
+            }
+            None => {
+                let value = parse_hex(range)?;
+                (value, value)
+            }
+        };
+        if first > last || last > char::MAX as u32 {
+            return Err(INVALID);
+        }
+
+        for (first, last) in [(first, last.min(0xD7FF)), (first.max(0xE000), last)] {
+            if let (Some(first), Some(last)) = (char::from_u32(first), char::from_u32(last)) {
+                if first <= last {
+                    ranges.push((first, last));
+                }
+            }
+        }
+    }
+    Ok(ranges)
+}
+
+fn parse_hex(value: &str) -> Result<u32, &'static str> {
+    if value.is_empty() || value.len() > 6 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("Invalid unicode-range");
+    }
+    Ok(u32::from_str_radix(value, 16).unwrap())
+}
+
+/// Tests whether the value of an `If-None-Match` header matches the entity tag.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    if_none_match.split(',').map(str::trim).any(|tag| {
+        tag == "*" || tag.strip_prefix("W/").unwrap_or(tag).as_bytes() == etag.as_bytes()
+    })
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    let mut response = Response::new(message.as_bytes().to_vec());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob};
+
+    fn service() -> SubsetService<'static> {
+        SubsetService::new(&FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap())
+    }
+
+    fn get(service: &SubsetService<'_>, uri: &str) -> Response<Vec<u8>> {
+        service.handle(&Request::get(uri).body(()).unwrap())
+    }
+
+    #[test]
+    fn serves_woff2_by_default() {
+        let service = service();
+        let response = get(&service, "/font?text=Hello");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "font/woff2");
+        assert_eq!(
+            response.headers()[CONTENT_LENGTH],
+            response.body().len().to_string().as_str()
+        );
+        assert!(response.headers()[CACHE_CONTROL]
+            .to_str()
+            .unwrap()
+            .contains("immutable"));
+        assert!(response.body().starts_with(b"wOF2"));
+    }
+
+    #[test]
+    fn serves_ttf_covering_requested_characters() {
+        let service = service();
+        let response = get(
+            &service,
+            "/font?text=a%20b&unicode-range=U%2B30-39&format=ttf",
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "font/ttf");
+
+        let font = FontFace::new(Blob::from_bytes(response.body()).unwrap()).unwrap();
+        let covered = font.covered_codepoints().unwrap();
+        assert_eq!(covered.len(), 13);
+        assert!(covered.contains('a'));
+        assert!(covered.contains(' '));
+        assert!(covered.contains('5'));
+    }
+
+    #[test]
+    fn etag_is_stable_and_honored() {
+        let service = service();
+        let first = get(&service, "/font?text=abc");
+        let second = get(&service, "/font?text=cba");
+        let etag = first.headers()[ETAG].clone();
+        assert_eq!(etag, second.headers()[ETAG]);
+        assert_ne!(
+            etag,
+            get(&service, "/font?text=abc&format=ttf").headers()[ETAG]
+        );
+        assert_ne!(etag, get(&service, "/font?text=abcd").headers()[ETAG]);
+
+        let request = Request::get("/font?text=abc")
+            .header(IF_NONE_MATCH, etag.clone())
+            .body(())
+            .unwrap();
+        let response = service.handle(&request);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.body().is_empty());
+        assert_eq!(response.headers()[ETAG], etag);
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        let service = service();
+        for uri in [
+            "/font",
+            "/font?format=ttf",
+            "/font?text=a&format=eot",
+            "/font?text=%ff",
+            "/font?unicode-range=0-7F",
+            "/font?unicode-range=U%2B7F-0",
+            "/font?unicode-range=U%2B110000",
+        ] {
+            assert_eq!(
+                get(&service, uri).status(),
+                StatusCode::BAD_REQUEST,
+                "{uri}"
+            );
+        }
+
+        let request = Request::post("/font?text=a").body(()).unwrap();
+        let response = service.handle(&request);
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, HEAD");
+    }
+
+    #[test]
+    fn unicode_range_is_parsed() {
+        assert_eq!(
+            parse_unicode_range("U+41, u+61-63,U+4??").unwrap(),
+            [('A', 'A'), ('a', 'c'), ('\u{400}', '\u{4ff}')]
+        );
+        assert_eq!(
+            parse_unicode_range("U+D000-E000").unwrap(),
+            [('\u{d000}', '\u{d7ff}'), ('\u{e000}', '\u{e000}')]
+        );
+        assert!(parse_unicode_range("U+4?1").is_err());
+        assert!(parse_unicode_range("U+").is_err());
+    }
+
+    #[test]
+    fn works_behind_a_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let service = service();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                // Minimal HTTP/1.0 server handling a single request
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut parts = request_line.split_whitespace();
+                let request = Request::builder()
+                    .method(parts.next().unwrap())
+                    .uri(parts.next().unwrap())
+                    .body(())
+                    .unwrap();
+
+                let response = service.handle(&request);
+                let mut stream = reader.into_inner();
+                write!(stream, "HTTP/1.0 {}\r\n", response.status()).unwrap();
+                for (name, value) in response.headers() {
+                    write!(stream, "{name}: {}\r\n", value.to_str().unwrap()).unwrap();
+                }
+                write!(stream, "\r\n").unwrap();
+                stream.write_all(response.body()).unwrap();
+            });
+
+            let mut stream = std::net::TcpStream::connect(address).unwrap();
+            write!(stream, "GET /font?text=Hi HTTP/1.0\r\n\r\n").unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).unwrap();
+            let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+            let head = String::from_utf8_lossy(&response[..split]);
+            assert!(head.starts_with("HTTP/1.0 200 OK"));
+            assert!(head.contains("content-type: font/woff2"));
+            assert!(response[split + 4..].starts_with(b"wOF2"));
+        });
+    }
+}
@@ -0,0 +1,560 @@
+//! Subsetting in a separate worker process.
+//!
+//! A memory budget protects against fonts which make HarfBuzz allocate heavily, but a pathological font can still
+//! keep the subsetter busy for a long time, and a bug in HarfBuzz can crash the whole process. [`IsolatedSubsetter`]
+//! runs each subsetting operation in a worker process instead, which is killed if it exceeds a wall-clock timeout,
+//! and whose resources are limited with `setrlimit`. A timed out or crashed worker is reported as an error, so a server
+//! can subset untrusted fonts without risking its own process.
+//!
+//! The worker is a program which calls [`run_worker`]. This crate ships one as binary `hb-subset-worker`, which is
+//! built with feature `isolated`. Alternatively, an application can act as its own worker by calling
+//! [`run_worker_if_requested`] at the start of its `main` and using [`IsolatedSubsetter::current_exe`]:
+//! ```no_run
+//! # use hb_subset::isolated::*;
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     // Serves the subsetting request and exits if this process was started as a worker
+//!     run_worker_if_requested();
+//!
+//!     let subsetter = IsolatedSubsetter::current_exe()?;
+//!     let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+//!     let subset_font = subsetter.subset(&font, "abc".chars())?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! This module is available only on Unix.
+
+use std::{
+    env,
+    ffi::OsString,
+    io::{self, Read, Write},
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{self, Child, Command, ExitStatus, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    subset::encoding, Blob, Capability, EmbeddingPermissions, FontFace, HarfBuzzVersion,
+    IsolatedSubsettingError, SubsetInput, SubsettingError, UnsupportedError,
+};
+
+/// Environment variable which tells [`run_worker_if_requested`] that the process was started as a worker.
+pub const WORKER_ENV: &str = "HB_SUBSET_WORKER";
+
+/// How long a worker may run by default.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Status byte of a response carrying the subset font.
+const RESPONSE_OK: u8 = 0;
+/// Status byte of a response carrying an error message.
+const RESPONSE_ERROR: u8 = 1;
+/// Status byte of a response carrying a [`SubsettingError`], encoded by [`encode_subsetting_error`].
+const RESPONSE_SUBSETTING_ERROR: u8 = 2;
+
+/// Capabilities which a worker can report as unsupported, in the order of their encoding.
+const CAPABILITIES: [Capability; 4] = [
+    Capability::AxisPinning,
+    Capability::NoLayoutClosure,
+    Capability::NameTableOverride,
+    Capability::MemoryBudget,
+];
+
+/// Subsets fonts in worker processes with limited resources.
+///
+/// Each call starts a new worker, so a misbehaving font can never affect later calls. The worker is killed if it does
+/// not finish within the timeout, which defaults to 10 seconds. The worker runs in a process group of its own, and any
+/// processes it starts are killed with it. The address space and CPU time of the worker are not limited by default.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use hb_subset::{*, isolated::IsolatedSubsetter};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let subsetter = IsolatedSubsetter::new("hb-subset-worker")
+///     .with_timeout(Duration::from_secs(2))
+///     .with_memory_limit(512 * 1024 * 1024);
+/// let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+/// match subsetter.subset(&font, "abc".chars()) {
+///     Ok(subset_font) => std::fs::write("tests/fonts/subset.ttf", subset_font)?,
+///     Err(IsolatedSubsettingError::TimedOut(_)) => eprintln!("The font took too long to subset"),
+///     Err(err) => return Err(err.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IsolatedSubsetter {
+    program: PathBuf,
+    args: Vec<OsString>,
+    timeout: Duration,
+    limits: Limits,
+}
+
+impl IsolatedSubsetter {
+    /// Creates a subsetter which runs the given worker program, e.g. `hb-subset-worker`.
+    ///
+    /// The program is looked up from `PATH` like with [`Command::new`].
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Creates a subsetter which runs the current executable as the worker.
+    ///
+    /// The executable must call [`run_worker_if_requested`] at the start of its `main`.
+    pub fn current_exe() -> io::Result<Self> {
+        Ok(Self::new(env::current_exe()?))
+    }
+
+    /// Passes the given arguments to the worker program.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets how long a worker may run before it is killed. Defaults to 10 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Limits the address space of a worker to the given number of bytes with `RLIMIT_AS`.
+    ///
+    /// The limit covers the whole worker process, including the font and the program itself, so it should be well
+    /// above the size of the fonts being subset. A worker running out of memory is reported as crashed.
+    pub fn with_memory_limit(mut self, bytes: u64) -> Self {
+        self.limits.memory = Some(bytes);
+        self
+    }
+
+    /// Limits the CPU time of a worker with `RLIMIT_CPU`. The limit is rounded up to whole seconds.
+    ///
+    /// Unlike the timeout, this does not count the time the worker spends waiting, e.g. on a busy machine.
+    pub fn with_cpu_time_limit(mut self, limit: Duration) -> Self {
+        let seconds = limit.as_secs() + u64::from(limit.subsec_nanos() > 0);
+        self.limits.cpu_time = Some(seconds);
+        self
+    }
+
+    /// Creates a subset of a font over given characters in a worker process, like [`subset()`](crate::subset()).
+    pub fn subset(
+        &self,
+        font: &[u8],
+        characters: impl IntoIterator<Item = char>,
+    ) -> Result<Vec<u8>, IsolatedSubsettingError> {
+        let mut input =
+            SubsetInput::new().map_err(|err| IsolatedSubsettingError::Failed(err.to_string()))?;
+        let mut unicode_set = input.unicode_set();
+        for char in characters {
+            unicode_set.insert(char);
+        }
+        self.subset_font(font, &input)
+    }
+
+    /// Subsets the font given as the bytes of a font file in a worker process, like [`SubsetInput::subset_font`].
+    ///
+    /// The memory budget of the input is applied in the worker, too. Errors of the subsetter are reported as
+    /// [`IsolatedSubsettingError::Subsetting`]. The subset is validated in the calling process, if enabled with
+    /// [`SubsetInput::set_validate_output`], so that [`SubsettingError::InvalidOutput`] can list the issues found.
+    pub fn subset_font(
+        &self,
+        font: &[u8],
+        input: &SubsetInput,
+    ) -> Result<Vec<u8>, IsolatedSubsettingError> {
+        let header = encode_request_header(input);
+        let limits = self.limits;
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .env(WORKER_ENV, "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0);
+        // SAFETY: Setting resource limits is async-signal-safe and does not allocate.
+        unsafe { command.pre_exec(move || limits.apply()) };
+        let deadline = Instant::now() + self.timeout;
+        let mut child = command.spawn()?;
+
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        let outcome = thread::scope(|scope| {
+            scope.spawn(move || {
+                // A worker which exits early closes its input, which is detected from the missing response instead
+                let _ = stdin.write_all(&header).and_then(|_| stdin.write_all(font));
+            });
+            let (sender, receiver) = mpsc::channel();
+            scope.spawn(move || {
+                let mut response = Vec::new();
+                let _ = sender.send(stdout.read_to_end(&mut response).map(|_| response));
+            });
+            let outcome = match receiver.recv_timeout(self.timeout) {
+                // The worker may keep running after closing its output, so waiting for it obeys the deadline, too
+                Ok(response) => wait_until(&mut child, deadline)
+                    .map(|status| status.map(|status| (response, status))),
+                Err(_) => Ok(None),
+            };
+            if !matches!(outcome, Ok(Some(_))) {
+                // Killing the whole group closes the pipes even if the worker has passed them on to processes of its
+                // own, which lets the threads above finish
+                kill_process_group(&child);
+            }
+            outcome
+        });
+        let (response, status) = match outcome {
+            Ok(Some(outcome)) => outcome,
+            outcome => {
+                // The worker was killed above, so this returns promptly
+                child.wait()?;
+                outcome?;
+                return Err(IsolatedSubsettingError::TimedOut(self.timeout));
+            }
+        };
+        let response = response?;
+        if !status.success() {
+            return Err(IsolatedSubsettingError::Crashed(status));
+        }
+        let new_font =
+            decode_response(&response).ok_or(IsolatedSubsettingError::InvalidResponse)??;
+        if input.validate_output() {
+            let blob = Blob::from_bytes(&new_font).map_err(|_| SubsettingError::Failed)?;
+            let issues = FontFace::new(blob)
+                .map_err(|_| SubsettingError::Failed)?
+                .validate();
+            if !issues.is_empty() {
+                return Err(SubsettingError::InvalidOutput(issues).into());
+            }
+        }
+        Ok(new_font)
+    }
+}
+
+/// Waits for the child to exit, or until the deadline passes. Returns [`None`] in the latter case.
+fn wait_until(child: &mut Child, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(10)));
+    }
+}
+
+/// Kills the child and every process in its process group.
+///
+/// The child leads the group, so the group cannot be reused by unrelated processes before the child is waited for.
+fn kill_process_group(child: &Child) {
+    unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+}
+
+/// Resource limits of a worker process.
+#[derive(Debug, Clone, Copy, Default)]
+struct Limits {
+    /// Maximum size of the address space in bytes.
+    memory: Option<u64>,
+    /// Maximum CPU time in seconds.
+    cpu_time: Option<u64>,
+}
+
+impl Limits {
+    /// Applies the limits to the current process. Core dumps are always disabled, as crashes are expected.
+    fn apply(self) -> io::Result<()> {
+        let limits = [
+            (libc::RLIMIT_CORE, Some(0)),
+            (libc::RLIMIT_AS, self.memory),
+            (libc::RLIMIT_CPU, self.cpu_time),
+        ];
+        for (resource, value) in limits {
+            let Some(value) = value else {
+                continue;
+            };
+            let limit = libc::rlimit {
+                rlim_cur: value as libc::rlim_t,
+                rlim_max: value as libc::rlim_t,
+            };
+            if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serves a subsetting request and exits if the process was started as a worker by [`IsolatedSubsetter`].
+///
+/// Otherwise, this returns immediately. Call this at the start of `main` when using
+/// [`IsolatedSubsetter::current_exe`].
+pub fn run_worker_if_requested() {
+    if env::var_os(WORKER_ENV).is_some() {
+        run_worker();
+    }
+}
+
+/// Serves a single subsetting request sent by [`IsolatedSubsetter`] over the standard input and output, and exits.
+pub fn run_worker() -> ! {
+    let mut request = Vec::new();
+    let response = match io::stdin().lock().read_to_end(&mut request) {
+        Ok(_) => handle_request(&request),
+        Err(err) => encode_error(&err),
+    };
+    let mut stdout = io::stdout().lock();
+    let result = stdout.write_all(&response).and_then(|_| stdout.flush());
+    process::exit(if result.is_ok() { 0 } else { 1 })
+}
+
+/// Encodes the part of a request preceding the font data.
+///
+/// The request consists of the length of the encoded subset input, the input itself, the memory budget, or
+/// [`u64::MAX`] if there is none, and finally the font data.
+fn encode_request_header(input: &SubsetInput) -> Vec<u8> {
+    let encoded = encoding::encode(input);
+    let budget = input
+        .memory_budget()
+        .map_or(u64::MAX, |budget| budget as u64);
+    let mut header = Vec::with_capacity(12 + encoded.len());
+    header.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+    header.extend_from_slice(&encoded);
+    header.extend_from_slice(&budget.to_le_bytes());
+    header
+}
+
+/// Subsets the font of a request, and encodes the result as a response.
+fn handle_request(request: &[u8]) -> Vec<u8> {
+    match subset_request(request) {
+        Ok(mut font) => {
+            font.insert(0, RESPONSE_OK);
+            font
+        }
+        Err(err) => match err.downcast::<SubsettingError>() {
+            Ok(err) => encode_subsetting_error(&err).unwrap_or_else(|| encode_error(&err)),
+            Err(err) => encode_error(&*err),
+        },
+    }
+}
+
+fn subset_request(request: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let (input, font) = decode_request(request).ok_or("Malformed subsetting request")?;
+    let font = FontFace::new(Blob::from_bytes(font).map_err(|_| SubsettingError::Failed)?)?;
+    let new_font = input.subset_font(&font)?;
+    let new_font = new_font.underlying_blob().to_vec();
+    Ok(new_font)
+}
+
+fn decode_request(request: &[u8]) -> Option<(SubsetInput, &[u8])> {
+    let (len, rest) = request.split_first_chunk::<4>()?;
+    let len = u32::from_le_bytes(*len) as usize;
+    if rest.len() < len {
+        return None;
+    }
+    let (input, rest) = rest.split_at(len);
+    let mut input = encoding::decode(input)?;
+    let (budget, font) = rest.split_first_chunk::<8>()?;
+    let budget = u64::from_le_bytes(*budget);
+    if budget != u64::MAX {
+        input.set_memory_budget(Some(usize::try_from(budget).ok()?));
+    }
+    // The calling process validates the subset, as the issues found cannot be sent back
+    input.set_validate_output(false);
+    Some((input, font))
+}
+
+fn encode_error(err: &dyn std::error::Error) -> Vec<u8> {
+    [&[RESPONSE_ERROR], err.to_string().as_bytes()].concat()
+}
+
+/// Encodes a subsetting error as a response, so that [`decode_response`] can rebuild it.
+///
+/// The response consists of the status byte, a byte telling the variant, and the fields of the variant. Returns
+/// [`None`] for errors which cannot be encoded, which are then sent as messages.
+fn encode_subsetting_error(err: &SubsettingError) -> Option<Vec<u8>> {
+    let mut response = vec![RESPONSE_SUBSETTING_ERROR];
+    match err {
+        SubsettingError::Failed => response.push(0),
+        SubsettingError::EmbeddingRestricted(permissions) => {
+            response.push(1);
+            response.extend_from_slice(&permissions.fs_type().to_le_bytes());
+        }
+        SubsettingError::Unsupported(err) => {
+            response.push(2);
+            response.push(CAPABILITIES.iter().position(|&c| c == err.capability)? as u8);
+            for part in [err.version.major, err.version.minor, err.version.micro] {
+                response.extend_from_slice(&part.to_le_bytes());
+            }
+        }
+        SubsettingError::MemoryBudgetExceeded(budget) => {
+            response.push(3);
+            response.extend_from_slice(&(*budget as u64).to_le_bytes());
+        }
+        SubsettingError::InvalidOutput(_) => return None,
+    }
+    Some(response)
+}
+
+fn decode_response(response: &[u8]) -> Option<Result<Vec<u8>, IsolatedSubsettingError>> {
+    let err = match response.split_first()? {
+        (&RESPONSE_OK, font) => return Some(Ok(font.to_vec())),
+        (&RESPONSE_ERROR, message) => {
+            IsolatedSubsettingError::Failed(String::from_utf8_lossy(message).into_owned())
+        }
+        (&RESPONSE_SUBSETTING_ERROR, err) => decode_subsetting_error(err)?.into(),
+        _ => return None,
+    };
+    Some(Err(err))
+}
+
+fn decode_subsetting_error(err: &[u8]) -> Option<SubsettingError> {
+    let (&variant, fields) = err.split_first()?;
+    let err = match (variant, fields) {
+        (0, []) => SubsettingError::Failed,
+        (1, &[a, b]) => {
+            let permissions = EmbeddingPermissions::from_fs_type(u16::from_le_bytes([a, b]));
+            SubsettingError::EmbeddingRestricted(permissions)
+        }
+        (2, [capability, version @ ..]) if version.len() == 12 => {
+            let part = |i: usize| u32::from_le_bytes(version[i * 4..][..4].try_into().unwrap());
+            SubsettingError::Unsupported(UnsupportedError {
+                capability: *CAPABILITIES.get(usize::from(*capability))?,
+                version: HarfBuzzVersion::new(part(0), part(1), part(2)),
+            })
+        }
+        (3, budget) => {
+            let budget = u64::from_le_bytes(budget.try_into().ok()?);
+            SubsettingError::MemoryBudgetExceeded(usize::try_from(budget).ok()?)
+        }
+        _ => return None,
+    };
+    Some(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::process::ExitStatusExt, time::Instant};
+
+    use super::*;
+    use crate::{tests::NOTO_SANS, Tag};
+
+    #[test]
+    fn worker_subsets_requested_font() {
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+        input.drop_table_tag_set().insert(Tag::new(b"GPOS"));
+        let request = [&encode_request_header(&input)[..], &font].concat();
+
+        let subset = decode_response(&handle_request(&request)).unwrap().unwrap();
+        assert_eq!(
+            subset,
+            input
+                .subset_font(&FontFace::new(Blob::from_bytes(&font).unwrap()).unwrap())
+                .unwrap()
+                .underlying_blob()
+                .to_vec()
+        );
+
+        match decode_response(&handle_request(b"garbage")) {
+            Some(Err(IsolatedSubsettingError::Failed(message))) => {
+                assert_eq!(message, "Malformed subsetting request")
+            }
+            result => panic!("Unexpected result {result:?}"),
+        }
+        assert!(decode_response(&[]).is_none());
+    }
+
+    #[test]
+    fn worker_reports_subsetting_errors() {
+        let errors = [
+            SubsettingError::Failed,
+            SubsettingError::EmbeddingRestricted(EmbeddingPermissions::from_fs_type(0x0002)),
+            SubsettingError::Unsupported(UnsupportedError {
+                capability: Capability::MemoryBudget,
+                version: HarfBuzzVersion::new(8, 3, 1),
+            }),
+            SubsettingError::MemoryBudgetExceeded(1024),
+        ];
+        for err in errors {
+            let response = encode_subsetting_error(&err).unwrap();
+            match decode_response(&response) {
+                Some(Err(IsolatedSubsettingError::Subsetting(decoded))) => {
+                    assert_eq!(format!("{decoded:?}"), format!("{err:?}"))
+                }
+                result => panic!("Unexpected result {result:?}"),
+            }
+        }
+        assert!(decode_response(&[RESPONSE_SUBSETTING_ERROR, 3, 0]).is_none());
+
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+        if Capability::MemoryBudget.is_supported() {
+            input.set_memory_budget(Some(1024));
+            let request = [&encode_request_header(&input)[..], &font].concat();
+            assert!(matches!(
+                decode_response(&handle_request(&request)),
+                Some(Err(IsolatedSubsettingError::Subsetting(
+                    SubsettingError::MemoryBudgetExceeded(1024)
+                )))
+            ));
+        }
+    }
+
+    #[test]
+    fn slow_worker_times_out() {
+        let subsetter = IsolatedSubsetter::new("sleep")
+            .with_args(["10"])
+            .with_timeout(Duration::from_millis(100));
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        let start = Instant::now();
+        assert!(matches!(
+            subsetter.subset(&font, "abc".chars()),
+            Err(IsolatedSubsettingError::TimedOut(_))
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn lingering_worker_times_out() {
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        for script in [
+            // Closes its output but keeps running
+            "exec >&-; sleep 10",
+            // Leaves the pipes open in a process of its own
+            "sleep 10; :",
+        ] {
+            let subsetter = IsolatedSubsetter::new("sh")
+                .with_args(["-c", script])
+                .with_timeout(Duration::from_millis(100));
+            let start = Instant::now();
+            assert!(matches!(
+                subsetter.subset(&font, "abc".chars()),
+                Err(IsolatedSubsettingError::TimedOut(_))
+            ));
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn crashed_worker_is_reported() {
+        let subsetter = IsolatedSubsetter::new("sh").with_args(["-c", "kill -9 $$"]);
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        match subsetter.subset(&font, "abc".chars()) {
+            Err(IsolatedSubsettingError::Crashed(status)) => assert_eq!(status.signal(), Some(9)),
+            result => panic!("Unexpected result {result:?}"),
+        }
+
+        let subsetter = IsolatedSubsetter::new("true");
+        assert!(matches!(
+            subsetter.subset(&font, "abc".chars()),
+            Err(IsolatedSubsettingError::InvalidResponse)
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+use std::ptr::null_mut;
+
+use crate::{
+    set::{GlyphSet, U32Set},
+    sys, AllocationError, FontFace, Tag,
+};
+
+/// Functions for querying OpenType layout tables (`GSUB` and `GPOS`).
+impl<'a> FontFace<'a> {
+    /// Fetches the tags of all features in the given layout table.
+    ///
+    /// The table is either `GSUB` or `GPOS`. Each tag is listed once, even if the table has several features with the
+    /// same tag, e.g. for different scripts.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// assert!(font.layout_feature_tags(Tag::new(b"GSUB")).contains(&Tag::new(b"liga")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_ot_layout_table_get_feature_tags")]
+    pub fn layout_feature_tags(&self, table: Tag) -> Vec<Tag> {
+        let count = unsafe {
+            sys::hb_ot_layout_table_get_feature_tags(
+                self.as_raw(),
+                table.into(),
+                0,
+                null_mut(),
+                null_mut(),
+            )
+        };
+        let mut len = count;
+        let mut tags = vec![0; count as usize];
+        unsafe {
+            sys::hb_ot_layout_table_get_feature_tags(
+                self.as_raw(),
+                table.into(),
+                0,
+                &mut len,
+                tags.as_mut_ptr(),
+            )
+        };
+        tags.truncate(len as usize);
+        let mut tags = tags.into_iter().map(Tag::from).collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    /// Fetches the tags of all scripts in the given layout table.
+    ///
+    /// The table is either `GSUB` or `GPOS`.
+    #[doc(alias = "hb_ot_layout_table_get_script_tags")]
+    pub fn layout_script_tags(&self, table: Tag) -> Vec<Tag> {
+        let count = unsafe {
+            sys::hb_ot_layout_table_get_script_tags(
+                self.as_raw(),
+                table.into(),
+                0,
+                null_mut(),
+                null_mut(),
+            )
+        };
+        let mut len = count;
+        let mut tags = vec![0; count as usize];
+        unsafe {
+            sys::hb_ot_layout_table_get_script_tags(
+                self.as_raw(),
+                table.into(),
+                0,
+                &mut len,
+                tags.as_mut_ptr(),
+            )
+        };
+        tags.truncate(len as usize);
+        tags.into_iter().map(Tag::from).collect()
+    }
+
+    /// Collects the indices of the lookups in the given layout table which are reachable from the given scripts,
+    /// languages and features.
+    ///
+    /// Passing [`None`] for any of the filters includes everything, e.g. all scripts. The table is either `GSUB` or
+    /// `GPOS`. The set is always empty when the bundled HarfBuzz is built with feature `bundled-lean` or
+    /// `bundled-mini`.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let gsub = Tag::new(b"GSUB");
+    /// let liga = font.collect_lookups(gsub, None, None, Some(&[Tag::new(b"liga")]))?;
+    /// let all = font.collect_lookups(gsub, None, None, None)?;
+    /// assert!(!liga.is_empty());
+    /// assert!(all.contains_set(&liga));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_ot_layout_collect_lookups")]
+    pub fn collect_lookups(
+        &self,
+        table: Tag,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+    ) -> Result<U32Set<'static>, AllocationError> {
+        let lookups = U32Set::new()?;
+        #[cfg(hb_subset_lean)]
+        let _ = (table, scripts, languages, features);
+        #[cfg(not(hb_subset_lean))]
+        {
+            // HarfBuzz expects the filters to be terminated with HB_TAG_NONE
+            fn terminated(tags: Option<&[Tag]>) -> Option<Vec<sys::hb_tag_t>> {
+                tags.map(|tags| tags.iter().map(|&tag| tag.into()).chain([0]).collect())
+            }
+            fn as_ptr(tags: &Option<Vec<sys::hb_tag_t>>) -> *const sys::hb_tag_t {
+                tags.as_ref().map_or(std::ptr::null(), |tags| tags.as_ptr())
+            }
+
+            let scripts = terminated(scripts);
+            let languages = terminated(languages);
+            let features = terminated(features);
+            unsafe {
+                sys::hb_ot_layout_collect_lookups(
+                    self.as_raw(),
+                    table.into(),
+                    as_ptr(&scripts),
+                    as_ptr(&languages),
+                    as_ptr(&features),
+                    lookups.as_raw(),
+                )
+            };
+        }
+        Ok(lookups)
+    }
+
+    /// Collects the glyphs a lookup in the given layout table may match or produce.
+    ///
+    /// The table is either `GSUB` or `GPOS`, and the lookup index is one returned by [`Self::collect_lookups`].
+    /// The sets are always empty when the bundled HarfBuzz is built with feature `bundled-lean` or `bundled-mini`.
+    #[doc(alias = "hb_ot_layout_lookup_collect_glyphs")]
+    pub fn lookup_collect_glyphs(
+        &self,
+        table: Tag,
+        lookup_index: u32,
+    ) -> Result<LookupGlyphs, AllocationError> {
+        let glyphs = LookupGlyphs {
+            before: GlyphSet::new()?,
+            input: GlyphSet::new()?,
+            after: GlyphSet::new()?,
+            output: GlyphSet::new()?,
+        };
+        #[cfg(hb_subset_lean)]
+        let _ = (table, lookup_index);
+        #[cfg(not(hb_subset_lean))]
+        unsafe {
+            sys::hb_ot_layout_lookup_collect_glyphs(
+                self.as_raw(),
+                table.into(),
+                lookup_index,
+                glyphs.before.as_raw(),
+                glyphs.input.as_raw(),
+                glyphs.after.as_raw(),
+                glyphs.output.as_raw(),
+            )
+        };
+        Ok(glyphs)
+    }
+
+    /// Extends the given glyph set with all glyphs the given `GSUB` lookups can substitute them with.
+    ///
+    /// The closure is computed transitively, so glyphs produced by one lookup are fed to the others. This is the same
+    /// closure the subsetter computes for layout features, and can be used for finding out why a glyph is retained.
+    /// The glyph set is left as is when the bundled HarfBuzz is built with feature `bundled-lean` or `bundled-mini`.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let cmap = font.nominal_glyph_mapping()?;
+    /// let mut glyphs = set::GlyphSet::new()?;
+    /// glyphs.insert(cmap.get('f').unwrap());
+    /// glyphs.insert(cmap.get('i').unwrap());
+    ///
+    /// let liga = font.collect_lookups(Tag::new(b"GSUB"), None, None, Some(&[Tag::new(b"liga")]))?;
+    /// font.lookups_substitute_closure(&liga, &mut glyphs);
+    /// // f, i, and the ligatures ﬁ, ﬀ and ﬃ
+    /// assert_eq!(glyphs.len(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_ot_layout_lookups_substitute_closure")]
+    pub fn lookups_substitute_closure(&self, lookups: &U32Set<'_>, glyphs: &mut GlyphSet<'_>) {
+        #[cfg(hb_subset_lean)]
+        let _ = (lookups, glyphs);
+        #[cfg(not(hb_subset_lean))]
+        unsafe {
+            sys::hb_ot_layout_lookups_substitute_closure(
+                self.as_raw(),
+                lookups.as_raw(),
+                glyphs.as_raw(),
+            )
+        }
+    }
+}
+
+/// Glyphs matched or produced by a layout lookup.
+///
+/// See [`FontFace::lookup_collect_glyphs`].
+#[derive(Debug, Clone)]
+pub struct LookupGlyphs {
+    /// Glyphs the lookup may match as backtrack context, before the input glyphs.
+    pub before: GlyphSet<'static>,
+    /// Glyphs the lookup may apply to.
+    pub input: GlyphSet<'static>,
+    /// Glyphs the lookup may match as lookahead context, after the input glyphs.
+    pub after: GlyphSet<'static>,
+    /// Glyphs the lookup may produce.
+    pub output: GlyphSet<'static>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob};
+
+    #[test]
+    fn layout_tags_are_listed() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let gsub = font.layout_feature_tags(Tag::new(b"GSUB"));
+        assert!(gsub.contains(&Tag::new(b"liga")));
+        assert!(!gsub.contains(&Tag::new(b"kern")));
+        assert!(gsub.windows(2).all(|w| w[0] < w[1]));
+        assert!(font
+            .layout_feature_tags(Tag::new(b"GPOS"))
+            .contains(&Tag::new(b"kern")));
+
+        let scripts = font.layout_script_tags(Tag::new(b"GSUB"));
+        assert!(scripts.contains(&Tag::new(b"latn")));
+        assert!(scripts.contains(&Tag::new(b"cyrl")));
+
+        assert!(font.layout_feature_tags(Tag::new(b"XXXX")).is_empty());
+    }
+
+    #[test]
+    fn ligature_lookups_close_over_ligatures() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let cmap = font.nominal_glyph_mapping().unwrap();
+        let gsub = Tag::new(b"GSUB");
+        let (f, i) = (cmap.get('f').unwrap(), cmap.get('i').unwrap());
+
+        let liga = font
+            .collect_lookups(gsub, None, None, Some(&[Tag::new(b"liga")]))
+            .unwrap();
+        let none = font.collect_lookups(gsub, Some(&[]), None, None).unwrap();
+        assert!(!liga.is_empty());
+        assert!(none.is_empty());
+
+        let mut input = GlyphSet::new().unwrap();
+        let mut output = GlyphSet::new().unwrap();
+        for lookup in &liga {
+            let glyphs = font.lookup_collect_glyphs(gsub, lookup).unwrap();
+            input.union(&glyphs.input);
+            output.union(&glyphs.output);
+        }
+        assert!(input.contains(f));
+
+        let mut glyphs = GlyphSet::new().unwrap();
+        glyphs.insert(f);
+        glyphs.insert(i);
+        font.lookups_substitute_closure(&liga, &mut glyphs);
+        assert_eq!(glyphs.len(), 5);
+        assert!(output.contains_set(&{
+            let mut ligatures = glyphs.clone();
+            ligatures.remove(f);
+            ligatures.remove(i);
+            ligatures
+        }));
+    }
+}
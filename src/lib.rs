@@ -16,7 +16,7 @@
 //!
 //! In other words, subsetting allows you to take a large font and construct a new, smaller font which has only those
 //! characters that you need. Be sure to check the license of the font though, as not all fonts can be legally
-//! subsetted.
+//! subsetted. The embedding permissions declared by the font can be enforced with [`EmbeddingPolicy`].
 //!
 //! # Why?
 //! Many modern fonts can contain hundreds or even thousands of glyphs, of which only a couple dozen or maybe hundred is
@@ -66,43 +66,191 @@
 //! ```bash
 //! cargo add hb-subset --features bundled
 //! ```
+//!
+//! The scope of the bundled build can be tuned with further features:
+//! - `bundled-subset-only` leaves out the parts of HarfBuzz the subsetter does not need, e.g. the shaper. None of the
+//!   wrappers in this crate use those parts.
+//! - `bundled-lean` builds HarfBuzz with `HB_LEAN`, which leaves out rarely needed parts of HarfBuzz for a smaller
+//!   binary. The subsetter then cannot subset CFF-based fonts, instance variable fonts, or subset the `GSUB` and `GPOS`
+//!   tables. Some wrappers do not work either:
+//!   - [`FontFace::ot_name`] and the convenience methods built on it return empty strings. [`FontFace::ot_names`]
+//!     still works.
+//!   - [`FontFace::covered_codepoints`], [`FontFace::nominal_glyph_mapping`] and [`FontFace::coverage_report`] return
+//!     empty results.
+//!   - [`FontFace::collect_lookups`] and [`FontFace::lookup_collect_glyphs`] return empty sets, and
+//!     [`FontFace::lookups_substitute_closure`] does nothing.
+//!   - Pinning variation axes fails, see [`Capability::AxisPinning`], and name table overrides are unsupported.
+//!   - [`Blob::from_file`] reads the file into memory instead of memory mapping it.
+//! - `bundled-mini` also builds HarfBuzz with `HB_MINI`, which further leaves out the `AAT` tables and legacy APIs. It
+//!   disables the same wrappers as `bundled-lean`.
+//! - `bundled-full` builds all of HarfBuzz even if one of the features above is enabled, e.g. by another crate. Use it
+//!   if you need the parts they leave out.
+//! - `custom-allocator` makes HarfBuzz allocate memory through the `allocator` module, which makes it possible to
+//!   limit the memory subsetting may use with [`SubsetInput::set_memory_budget`]. Allocations are then tracked per
+//!   thread, which adds a little overhead to each of them.
+//! - `experimental` enables experimental HarfBuzz APIs.
+//!
+//! The bundled copy is also used as a fallback if the system HarfBuzz is not found, or it is older than 7.0.
+//!
+//! Feature `bindgen`, enabled by default, generates the raw bindings in the `sys` module at build time, which requires
+//! libclang. Disabling it makes the build use the pre-generated bindings from `bindings/harfbuzz.rs` instead. These
+//! are not shipped yet, so for now the feature has to stay enabled.
+//!
+//! The version of HarfBuzz actually in use can be queried with [`harfbuzz_version`]. Some features of this crate need a
+//! newer HarfBuzz than the oldest supported one, 7.0. They are listed in [`Capability`], and using them with an older
+//! HarfBuzz results in an error instead of a link failure.
+//!
+//! # WebAssembly
+//! The crate can be built for `wasm32-wasip1`, e.g. for edge workers, and for `wasm32-unknown-unknown`, e.g. for
+//! browsers. The bundled HarfBuzz is always used on WebAssembly. Compiling it requires a C++ compiler targeting
+//! WebAssembly, and the WASI sysroot for the C and C++ headers, given in the `WASI_SYSROOT` environment variable. Both
+//! are included in
+//! [wasi-sdk](https://github.com/WebAssembly/wasi-sdk):
+//! ```bash
+//! export WASI_SYSROOT=/opt/wasi-sdk/share/wasi-sysroot CXX_wasm32_wasip1=/opt/wasi-sdk/bin/clang++
+//! cargo test --target wasm32-wasip1
+//! ```
+//! The tests are run under [wasmtime](https://wasmtime.dev/), as configured in `.cargo/config.toml`.
+//!
+//! Feature `wasm-bindgen` adds JavaScript bindings for subsetting fonts, see the `wasm` module.
+//!
+//! # Subsetting untrusted fonts
+//! Besides limiting memory with [`subset_with_memory_budget`], fonts from untrusted sources can be subset in a separate
+//! worker process on Unix with feature `isolated`. The worker is killed if it does not finish in time, and its
+//! resources are limited, so a malicious font cannot hang or crash the calling process. See the `isolated` module.
 
 #![warn(missing_docs)]
 
+#[cfg(hb_subset_rust_allocator)]
+pub mod allocator;
 mod blob;
+#[cfg(feature = "cache")]
+pub mod cache;
 mod common;
+mod coverage;
+mod embedding;
 mod error;
 mod font_face;
+mod glyph;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(all(feature = "isolated", unix))]
+pub mod isolated;
+mod layout;
 pub mod map;
+mod name_id;
+mod name_language;
+mod name_table;
+mod outline;
+mod report;
 pub mod set;
 mod subset;
+pub mod tags;
+mod unicode_block;
+mod validate;
+mod version;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+#[cfg(feature = "woff2")]
+pub mod woff2;
 
 pub mod sys;
 
 pub use blob::*;
 pub use common::*;
+pub use coverage::*;
+pub use embedding::*;
 pub use error::*;
 pub use font_face::*;
+pub use glyph::*;
+pub use layout::*;
+pub use name_id::*;
+pub use report::*;
 pub use subset::*;
+pub use unicode_block::*;
+pub use validate::*;
+pub use version::*;
 
 /// A convenient method to create a subset of a font over given characters.
 ///
 /// The returned font can be used everywhere where the original font was used, as long as the string contains only
 /// characters from the given set. In particular, the font includes all relevant ligatures.
+///
+/// The embedding permissions of the font are not checked. See [`subset_with_policy`] for a variant which can refuse to
+/// subset fonts whose license forbids it.
 pub fn subset(
     font: &[u8],
     characters: impl IntoIterator<Item = char>,
+) -> Result<Vec<u8>, SubsettingError> {
+    subset_with_policy(font, characters, EmbeddingPolicy::Ignore)
+}
+
+/// Like [`subset()`], but treats the embedding permissions of the font according to the given policy.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+/// match hb_subset::subset_with_policy(&font, "abc".chars(), EmbeddingPolicy::Enforce) {
+///     Ok(subset_font) => std::fs::write("tests/fonts/subset.ttf", subset_font)?,
+///     Err(SubsettingError::EmbeddingRestricted(permissions)) => {
+///         eprintln!("The font may not be subsetted: {permissions:?}")
+///     }
+///     Err(err) => return Err(err.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn subset_with_policy(
+    font: &[u8],
+    characters: impl IntoIterator<Item = char>,
+    policy: EmbeddingPolicy,
+) -> Result<Vec<u8>, SubsettingError> {
+    subset_impl(font, characters, policy, None)
+}
+
+/// Like [`subset()`], but limits the memory HarfBuzz may allocate for subsetting to the given number of bytes.
+///
+/// Use this for fonts which cannot be trusted. If the budget is exceeded, [`SubsettingError::MemoryBudgetExceeded`] is
+/// returned. See [`SubsetInput::set_memory_budget`] for details.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # if !Capability::MemoryBudget.is_supported() { return Ok(()); }
+/// let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+/// let subset_font = hb_subset::subset_with_memory_budget(&font, "abc".chars(), 64 * 1024 * 1024)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn subset_with_memory_budget(
+    font: &[u8],
+    characters: impl IntoIterator<Item = char>,
+    budget: usize,
+) -> Result<Vec<u8>, SubsettingError> {
+    subset_impl(font, characters, EmbeddingPolicy::Ignore, Some(budget))
+}
+
+fn subset_impl(
+    font: &[u8],
+    characters: impl IntoIterator<Item = char>,
+    policy: EmbeddingPolicy,
+    memory_budget: Option<usize>,
 ) -> Result<Vec<u8>, SubsettingError> {
     // Add all characters to subset, and nothing more.
-    let mut subset = SubsetInput::new().map_err(|_| SubsettingError)?;
+    let mut subset = SubsetInput::new().map_err(|_| SubsettingError::Failed)?;
+    subset.set_embedding_policy(policy);
+    subset.set_memory_budget(memory_budget);
     let mut unicode_set = subset.unicode_set();
     for char in characters {
         unicode_set.insert(char);
     }
 
     // Load the original font, and then construct a subset from it
-    let font = FontFace::new(Blob::from_bytes(font).map_err(|_| SubsettingError)?)
-        .map_err(|_| SubsettingError)?;
+    let font = FontFace::new(Blob::from_bytes(font).map_err(|_| SubsettingError::Failed)?)
+        .map_err(|_| SubsettingError::Failed)?;
     let new_font = subset.subset_font(&font)?;
     let new_font = new_font.underlying_blob().to_vec();
     Ok(new_font)
@@ -0,0 +1,226 @@
+use std::{fmt, num::TryFromIntError};
+
+use crate::sys;
+
+/// Identifier of a record in the OpenType name table.
+///
+/// Name ids below 256 are predefined by the OpenType specification, and the most common of them have associated
+/// constants, such as [`NameId::FONT_FAMILY`]. Name ids from 256 up are font-specific, and are referred to from other
+/// tables, e.g. for naming variation instances or stylistic sets. See the
+/// [OpenType spec](https://learn.microsoft.com/en-us/typography/opentype/spec/name#name-ids) for more information.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// assert_eq!(font.ot_name(NameId::FONT_FAMILY, Language::default()), "Noto Sans");
+///
+/// // Retain only family names and font-specific names in the subset
+/// let mut subset = SubsetInput::new()?;
+/// let mut name_ids = subset.name_id_set();
+/// name_ids.clear();
+/// name_ids.insert(NameId::FONT_FAMILY);
+/// name_ids.insert(NameId::TYPOGRAPHIC_FAMILY);
+/// name_ids.insert_range(NameId::FIRST_FONT_SPECIFIC..);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NameId(u16);
+
+impl NameId {
+    /// Copyright notice.
+    pub const COPYRIGHT: Self = Self(0);
+    /// Font family name.
+    pub const FONT_FAMILY: Self = Self(1);
+    /// Font subfamily name.
+    pub const FONT_SUBFAMILY: Self = Self(2);
+    /// Unique font identifier.
+    pub const UNIQUE_ID: Self = Self(3);
+    /// Full font name.
+    pub const FULL_NAME: Self = Self(4);
+    /// Version string.
+    pub const VERSION_STRING: Self = Self(5);
+    /// PostScript name.
+    pub const POSTSCRIPT_NAME: Self = Self(6);
+    /// Trademark notice.
+    pub const TRADEMARK: Self = Self(7);
+    /// Manufacturer name.
+    pub const MANUFACTURER: Self = Self(8);
+    /// Designer name.
+    pub const DESIGNER: Self = Self(9);
+    /// Description of the typeface.
+    pub const DESCRIPTION: Self = Self(10);
+    /// URL of the font vendor.
+    pub const VENDOR_URL: Self = Self(11);
+    /// URL of the designer.
+    pub const DESIGNER_URL: Self = Self(12);
+    /// License description.
+    pub const LICENSE: Self = Self(13);
+    /// URL of the license.
+    pub const LICENSE_URL: Self = Self(14);
+    /// Typographic family name.
+    pub const TYPOGRAPHIC_FAMILY: Self = Self(16);
+    /// Typographic subfamily name.
+    pub const TYPOGRAPHIC_SUBFAMILY: Self = Self(17);
+    /// Compatible full name for Macintosh.
+    pub const MAC_FULL_NAME: Self = Self(18);
+    /// Sample text.
+    pub const SAMPLE_TEXT: Self = Self(19);
+    /// PostScript CID findfont name.
+    pub const CID_FINDFONT_NAME: Self = Self(20);
+    /// WWS family name.
+    pub const WWS_FAMILY: Self = Self(21);
+    /// WWS subfamily name.
+    pub const WWS_SUBFAMILY: Self = Self(22);
+    /// Light background palette name.
+    pub const LIGHT_BACKGROUND: Self = Self(23);
+    /// Dark background palette name.
+    pub const DARK_BACKGROUND: Self = Self(24);
+    /// Variations PostScript name prefix.
+    pub const VARIATIONS_PS_PREFIX: Self = Self(25);
+    /// The first font-specific name id.
+    pub const FIRST_FONT_SPECIFIC: Self = Self(256);
+
+    /// Names of the predefined name ids, used for formatting.
+    const NAMES: [(Self, &'static str); 25] = [
+        (Self::COPYRIGHT, "COPYRIGHT"),
+        (Self::FONT_FAMILY, "FONT_FAMILY"),
+        (Self::FONT_SUBFAMILY, "FONT_SUBFAMILY"),
+        (Self::UNIQUE_ID, "UNIQUE_ID"),
+        (Self::FULL_NAME, "FULL_NAME"),
+        (Self::VERSION_STRING, "VERSION_STRING"),
+        (Self::POSTSCRIPT_NAME, "POSTSCRIPT_NAME"),
+        (Self::TRADEMARK, "TRADEMARK"),
+        (Self::MANUFACTURER, "MANUFACTURER"),
+        (Self::DESIGNER, "DESIGNER"),
+        (Self::DESCRIPTION, "DESCRIPTION"),
+        (Self::VENDOR_URL, "VENDOR_URL"),
+        (Self::DESIGNER_URL, "DESIGNER_URL"),
+        (Self::LICENSE, "LICENSE"),
+        (Self::LICENSE_URL, "LICENSE_URL"),
+        (Self::TYPOGRAPHIC_FAMILY, "TYPOGRAPHIC_FAMILY"),
+        (Self::TYPOGRAPHIC_SUBFAMILY, "TYPOGRAPHIC_SUBFAMILY"),
+        (Self::MAC_FULL_NAME, "MAC_FULL_NAME"),
+        (Self::SAMPLE_TEXT, "SAMPLE_TEXT"),
+        (Self::CID_FINDFONT_NAME, "CID_FINDFONT_NAME"),
+        (Self::WWS_FAMILY, "WWS_FAMILY"),
+        (Self::WWS_SUBFAMILY, "WWS_SUBFAMILY"),
+        (Self::LIGHT_BACKGROUND, "LIGHT_BACKGROUND"),
+        (Self::DARK_BACKGROUND, "DARK_BACKGROUND"),
+        (Self::VARIATIONS_PS_PREFIX, "VARIATIONS_PS_PREFIX"),
+    ];
+
+    /// Constructs a name id from its raw value.
+    pub const fn new(id: u16) -> Self {
+        Self(id)
+    }
+
+    /// Gets the raw value of the name id.
+    pub const fn to_u16(self) -> u16 {
+        self.0
+    }
+
+    /// Tests whether the name id is font-specific, i.e. at least 256, instead of predefined by the specification.
+    pub const fn is_font_specific(self) -> bool {
+        self.0 >= Self::FIRST_FONT_SPECIFIC.0
+    }
+}
+
+impl From<u16> for NameId {
+    fn from(id: u16) -> Self {
+        Self(id)
+    }
+}
+
+impl From<NameId> for u16 {
+    fn from(id: NameId) -> Self {
+        id.0
+    }
+}
+
+impl From<NameId> for u32 {
+    fn from(id: NameId) -> Self {
+        id.0 as u32
+    }
+}
+
+impl TryFrom<u32> for NameId {
+    type Error = TryFromIntError;
+
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        u16::try_from(id).map(Self)
+    }
+}
+
+impl From<sys::hb_ot_name_id_predefined_t> for NameId {
+    fn from(id: sys::hb_ot_name_id_predefined_t) -> Self {
+        Self(id.0 as u16)
+    }
+}
+
+impl From<sys::hb_ot_name_id_t> for NameId {
+    /// Converts a raw name id. Name ids are stored as 16-bit values in the name table, so HarfBuzz never produces
+    /// larger values.
+    fn from(id: sys::hb_ot_name_id_t) -> Self {
+        Self(id.0 as u16)
+    }
+}
+
+impl From<NameId> for sys::hb_ot_name_id_t {
+    fn from(id: NameId) -> Self {
+        Self(id.0 as _)
+    }
+}
+
+impl fmt::Debug for NameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Self::NAMES.iter().find(|(id, _)| id == self) {
+            Some((_, name)) => write!(f, "NameId::{name}"),
+            None => write!(f, "NameId({})", self.0),
+        }
+    }
+}
+
+impl fmt::Display for NameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::NameIdSet;
+
+    #[test]
+    fn name_ids_convert() {
+        assert_eq!(
+            NameId::from(sys::hb_ot_name_id_predefined_t::VARIATIONS_PS_PREFIX),
+            NameId::VARIATIONS_PS_PREFIX
+        );
+        assert_eq!(u32::from(NameId::TYPOGRAPHIC_FAMILY), 16);
+        assert!(NameId::try_from(0x10000u32).is_err());
+        assert!(!NameId::VARIATIONS_PS_PREFIX.is_font_specific());
+        assert!(NameId::new(256).is_font_specific());
+        assert_eq!(format!("{:?}", NameId::FONT_FAMILY), "NameId::FONT_FAMILY");
+        assert_eq!(format!("{:?}", NameId::new(300)), "NameId(300)");
+        assert_eq!(NameId::new(300).to_string(), "300");
+    }
+
+    #[test]
+    fn name_id_sets_contain_only_valid_ids() {
+        let mut set = NameIdSet::new().unwrap();
+        set.insert(NameId::LICENSE);
+        set.insert_range(NameId::new(u16::MAX - 1)..);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [
+                NameId::LICENSE,
+                NameId::new(u16::MAX - 1),
+                NameId::new(u16::MAX)
+            ]
+        );
+    }
+}
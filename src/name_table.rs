@@ -0,0 +1,226 @@
+//! Parsing of the OpenType `name` table.
+//!
+//! HarfBuzz only gives access to the best matching record for each name id and language, so listing all records, e.g.
+//! for auditing which strings a subset retains, requires reading the table directly.
+
+use std::str::FromStr;
+
+use crate::Language;
+
+/// Characters 0x80-0xFF of the Mac Roman encoding, which is used by records on the Macintosh platform.
+const MAC_ROMAN: &str = "ÄÅÇÉÑÖÜáàâäãåçéèêëíìîïñóòôöõúùûü\
+    †°¢£§•¶ß®©™´¨≠ÆØ∞±≤≥¥µ∂∑∏π∫ªºΩæø\
+    ¿¡¬√ƒ≈∆«»…\u{A0}ÀÃÕŒœ–—“”‘’÷◊ÿŸ⁄€‹›ﬁﬂ\
+    ‡·‚„‰ÂÊÁËÈÍÎÏÌÓÔ\u{F8FF}ÒÚÛÙıˆ˜¯˘˙˚¸˝˛ˇ";
+
+/// Platform id of Unicode records.
+const PLATFORM_UNICODE: u16 = 0;
+/// Platform id of Macintosh records.
+const PLATFORM_MAC: u16 = 1;
+/// Platform id of Windows records.
+const PLATFORM_WINDOWS: u16 = 3;
+/// The first language id which refers to a language tag record of a format 1 name table.
+const FIRST_LANG_TAG_ID: u16 = 0x8000;
+
+/// A single record of the name table.
+#[derive(Debug, Clone)]
+pub(crate) struct NameTableRecord {
+    pub(crate) platform_id: u16,
+    pub(crate) encoding_id: u16,
+    pub(crate) language_id: u16,
+    pub(crate) name_id: u16,
+    /// Language of the record, or the invalid language if the language id is not known.
+    pub(crate) language: Language,
+    pub(crate) value: String,
+}
+
+/// Reads all records of a name table, in the order they appear in the table.
+///
+/// Malformed records, e.g. ones pointing outside of the table, are skipped.
+pub(crate) fn records(table: &[u8]) -> Vec<NameTableRecord> {
+    let (Some(format), Some(count), Some(storage)) =
+        (read_u16(table, 0), read_u16(table, 2), read_u16(table, 4))
+    else {
+        return Vec::new();
+    };
+    let storage = table.get(storage as usize..).unwrap_or_default();
+    let lang_tags_offset = 6 + 12 * count as usize;
+    let lang_tag_count = if format == 1 {
+        read_u16(table, lang_tags_offset).unwrap_or(0)
+    } else {
+        0
+    };
+
+    (0..count as usize)
+        .filter_map(|idx| {
+            let record = table.get(6 + 12 * idx..6 + 12 * (idx + 1))?;
+            let field = |idx: usize| u16::from_be_bytes([record[2 * idx], record[2 * idx + 1]]);
+            let (platform_id, encoding_id, language_id, name_id) =
+                (field(0), field(1), field(2), field(3));
+            let data = string(storage, field(4), field(5))?;
+
+            let language = if language_id >= FIRST_LANG_TAG_ID {
+                let idx = language_id - FIRST_LANG_TAG_ID;
+                (idx < lang_tag_count)
+                    .then(|| {
+                        let offset = lang_tags_offset + 2 + 4 * idx as usize;
+                        let tag = string(
+                            storage,
+                            read_u16(table, offset)?,
+                            read_u16(table, offset + 2)?,
+                        )?;
+                        Language::from_str(&decode_utf16(tag)).ok()
+                    })
+                    .flatten()
+            } else {
+                match platform_id {
+                    PLATFORM_MAC => Language::from_mac_language_id(language_id),
+                    PLATFORM_WINDOWS => Language::from_windows_language_id(language_id),
+                    _ => None,
+                }
+            };
+
+            Some(NameTableRecord {
+                platform_id,
+                encoding_id,
+                language_id,
+                name_id,
+                language: language.unwrap_or_default(),
+                value: decode(platform_id, encoding_id, data),
+            })
+        })
+        .collect()
+}
+
+/// Decodes the value of a record.
+///
+/// Values in encodings other than UTF-16 and Mac Roman, e.g. legacy Windows CJK encodings, are decoded lossily.
+fn decode(platform_id: u16, encoding_id: u16, data: &[u8]) -> String {
+    match (platform_id, encoding_id) {
+        (PLATFORM_UNICODE | PLATFORM_WINDOWS, _) => decode_utf16(data),
+        (PLATFORM_MAC, 0) => data
+            .iter()
+            .map(|&byte| match byte {
+                0..=0x7F => byte as char,
+                _ => MAC_ROMAN.chars().nth(byte as usize - 0x80).unwrap(),
+            })
+            .collect(),
+        _ => String::from_utf8_lossy(data).into_owned(),
+    }
+}
+
+fn decode_utf16(data: &[u8]) -> String {
+    let units = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+        .collect::<Vec<_>>();
+    String::from_utf16_lossy(&units)
+}
+
+fn string(storage: &[u8], length: u16, offset: u16) -> Option<&[u8]> {
+    storage.get(offset as usize..offset as usize + length as usize)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds a name table from records of platform id, encoding id, language id, name id and value. The values of
+    /// Macintosh records are encoded as ASCII, all others as UTF-16.
+    pub(crate) fn name_table(
+        records: &[(u16, u16, u16, u16, &str)],
+        lang_tags: &[&str],
+    ) -> Vec<u8> {
+        let encode = |platform_id: u16, value: &str| -> Vec<u8> {
+            if platform_id == PLATFORM_MAC {
+                value.bytes().collect()
+            } else {
+                value.encode_utf16().flat_map(u16::to_be_bytes).collect()
+            }
+        };
+        let format = u16::from(!lang_tags.is_empty());
+        let header_len = 6
+            + 12 * records.len()
+            + if lang_tags.is_empty() {
+                0
+            } else {
+                2 + 4 * lang_tags.len()
+            };
+        let mut header = Vec::new();
+        let mut storage = Vec::new();
+        for value in [format, records.len() as u16, header_len as u16] {
+            header.extend(value.to_be_bytes());
+        }
+        for &(platform_id, encoding_id, language_id, name_id, value) in records {
+            let value = encode(platform_id, value);
+            for field in [
+                platform_id,
+                encoding_id,
+                language_id,
+                name_id,
+                value.len() as u16,
+                storage.len() as u16,
+            ] {
+                header.extend(field.to_be_bytes());
+            }
+            storage.extend(value);
+        }
+        if !lang_tags.is_empty() {
+            header.extend((lang_tags.len() as u16).to_be_bytes());
+            for tag in lang_tags {
+                let tag = encode(PLATFORM_WINDOWS, tag);
+                header.extend((tag.len() as u16).to_be_bytes());
+                header.extend((storage.len() as u16).to_be_bytes());
+                storage.extend(tag);
+            }
+        }
+        [header, storage].concat()
+    }
+
+    #[test]
+    fn records_are_read_with_their_own_values() {
+        let table = name_table(
+            &[
+                (1, 0, 2, 1, "Mac"),
+                (3, 1, 0x0409, 1, "English"),
+                (3, 1, 0x040C, 1, "Français"),
+                (3, 1, 0x7777, 1, "Unknown"),
+                (3, 1, 0x8000, 1, "Tagged"),
+            ],
+            &["fr-CA"],
+        );
+        let parsed = records(&table);
+        let values = parsed
+            .iter()
+            .map(|record| (record.language.to_string(), record.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                ("de".to_string(), "Mac"),
+                ("en".to_string(), "English"),
+                ("fr".to_string(), "Français"),
+                ("[invalid]".to_string(), "Unknown"),
+                ("fr-ca".to_string(), "Tagged"),
+            ]
+        );
+        assert!(parsed.iter().all(|record| record.name_id == 1));
+
+        // Truncated tables do not panic
+        for len in 0..table.len() {
+            records(&table[..len]);
+        }
+    }
+
+    #[test]
+    fn mac_roman_is_decoded() {
+        assert_eq!(MAC_ROMAN.chars().count(), 128);
+        assert_eq!(decode(1, 0, b"Caf\x8E \xA9"), "Café ©");
+        assert_eq!(decode(3, 1, &[0, b'A', 0x20, 0xAC]), "A€");
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::Range;
+
+use crate::{
+    sys,
+    validate::{read_u16, read_u32},
+    Blob, FontFace, GlyphId, Tag,
+};
+
+/// Functions for accessing raw outline data of individual glyphs.
+impl<'a> FontFace<'a> {
+    /// Gets the raw outline data of a glyph.
+    ///
+    /// For fonts with TrueType outlines, this is the slice of the `glyf` table that `loca` points to, including
+    /// instructions and possible padding. For fonts with CFF or CFF2 outlines, this is the charstring of the glyph. The
+    /// data is returned as is, so subroutine calls in charstrings are not expanded.
+    ///
+    /// Returns [`None`] if the font has no supported outline table, the glyph is out of range, or the tables are
+    /// malformed. Glyphs without an outline, such as space, have empty data.
+    ///
+    /// This works equally on original and subset fonts, which makes it possible to compare outlines byte-for-byte.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let cmap = font.nominal_glyph_mapping()?;
+    ///
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// subset.flags().remove_hinting();
+    /// let new_font = subset.subset_font(&font)?;
+    /// let new_cmap = new_font.nominal_glyph_mapping()?;
+    ///
+    /// let original = font.glyph_data(cmap.get('a').unwrap()).unwrap();
+    /// let unhinted = new_font.glyph_data(new_cmap.get('a').unwrap()).unwrap();
+    /// assert!(unhinted.len() < original.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_subset_cff_get_charstring_data")]
+    #[doc(alias = "hb_subset_cff2_get_charstring_data")]
+    pub fn glyph_data(&self, glyph: GlyphId) -> Option<Blob<'_>> {
+        let glyph = glyph.to_u32() as usize;
+
+        let glyf = self.table(Tag::new(b"glyf"));
+        if !glyf.is_empty() {
+            let loca = self.table(Tag::new(b"loca"));
+            let long_offsets = read_u16(&self.table(Tag::new(b"head")), 50)? != 0;
+            let offset = |glyph: usize| {
+                if long_offsets {
+                    read_u32(&loca, 4 * glyph).map(|offset| offset as usize)
+                } else {
+                    read_u16(&loca, 2 * glyph).map(|offset| 2 * offset as usize)
+                }
+            };
+            let range = offset(glyph)?..offset(glyph + 1)?;
+            if range.start > range.end || range.end > glyf.len() {
+                return None;
+            }
+            return sub_blob(&glyf, range);
+        }
+
+        let (table, cff2) = self.cff_table()?;
+        let index = Index::parse(&table, charstrings_offset(&table, cff2)?, cff2)?;
+        let range = index.get(glyph)?;
+        sub_blob(&table, range)
+    }
+
+    /// Gets the raw `CharStrings` INDEX of a font with CFF or CFF2 outlines.
+    ///
+    /// The INDEX contains the charstrings of all glyphs, in the format described in the CFF specification. Returns
+    /// [`None`] if the font has no CFF or CFF2 table, or the table is malformed.
+    #[doc(alias = "hb_subset_cff_get_charstrings_index")]
+    #[doc(alias = "hb_subset_cff2_get_charstrings_index")]
+    pub fn charstrings_index(&self) -> Option<Blob<'_>> {
+        let (table, cff2) = self.cff_table()?;
+        let offset = charstrings_offset(&table, cff2)?;
+        let end = Index::parse(&table, offset, cff2)?.end()?;
+        sub_blob(&table, offset..end)
+    }
+
+    /// Gets the CFF or CFF2 table of the font, and whether it is CFF2.
+    fn cff_table(&self) -> Option<(Blob<'_>, bool)> {
+        let cff = self.table(Tag::new(b"CFF "));
+        if !cff.is_empty() {
+            return Some((cff, false));
+        }
+        let cff2 = self.table(Tag::new(b"CFF2"));
+        if !cff2.is_empty() {
+            return Some((cff2, true));
+        }
+        None
+    }
+}
+
+/// Creates a blob which references a part of another blob.
+fn sub_blob<'b>(blob: &Blob<'b>, range: Range<usize>) -> Option<Blob<'b>> {
+    let blob = unsafe {
+        sys::hb_blob_create_sub_blob(
+            blob.as_raw(),
+            range.start.try_into().ok()?,
+            range.len().try_into().ok()?,
+        )
+    };
+    Some(unsafe { Blob::from_raw(blob) })
+}
+
+/// Finds the offset of the `CharStrings` INDEX from the top DICT of a CFF or CFF2 table.
+fn charstrings_offset(table: &[u8], cff2: bool) -> Option<usize> {
+    let header_size = *table.get(2)? as usize;
+    let top_dict = if cff2 {
+        let length = read_u16(table, 3)? as usize;
+        header_size..header_size.checked_add(length)?
+    } else {
+        // The top DICT INDEX follows the name INDEX
+        let names = Index::parse(table, header_size, false)?;
+        Index::parse(table, names.end()?, false)?.get(0)?
+    };
+    let offset = dict_operand(table.get(top_dict)?, 17)?;
+    usize::try_from(offset).ok()
+}
+
+/// Finds the last operand of the given operator in a DICT.
+fn dict_operand(mut dict: &[u8], operator: u8) -> Option<i32> {
+    let mut operand = None;
+    while let Some((&b0, rest)) = dict.split_first() {
+        dict = rest;
+        match b0 {
+            12 => {
+                // Two-byte operators are never the one looked for
+                dict = dict.get(1..)?;
+                operand = None;
+            }
+            0..=21 if b0 == operator => return operand,
+            0..=21 => operand = None,
+            28 => {
+                operand = Some(read_u16(dict, 0)? as i16 as i32);
+                dict = dict.get(2..)?;
+            }
+            29 => {
+                operand = Some(read_u32(dict, 0)? as i32);
+                dict = dict.get(4..)?;
+            }
+            30 => {
+                // Real numbers end in a nibble of 0xf
+                let end = dict.iter().position(|b| b & 0xf == 0xf || b >> 4 == 0xf)?;
+                dict = &dict[end + 1..];
+                operand = None;
+            }
+            32..=246 => operand = Some(b0 as i32 - 139),
+            247..=250 => {
+                operand = Some((b0 as i32 - 247) * 256 + *dict.first()? as i32 + 108);
+                dict = &dict[1..];
+            }
+            251..=254 => {
+                operand = Some(-(b0 as i32 - 251) * 256 - *dict.first()? as i32 - 108);
+                dict = &dict[1..];
+            }
+            255 => {
+                // 16.16 fixed point number, only in CFF2
+                dict = dict.get(4..)?;
+                operand = None;
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// An INDEX structure of a CFF or CFF2 table.
+struct Index<'d> {
+    data: &'d [u8],
+    count: usize,
+    offset_size: usize,
+    /// Position of the offset array.
+    offsets: usize,
+}
+
+impl<'d> Index<'d> {
+    /// Parses the header of an INDEX at the given position. The count of CFF2 INDEXes is 32-bit.
+    fn parse(data: &'d [u8], position: usize, cff2: bool) -> Option<Self> {
+        let (count, offsets) = if cff2 {
+            (read_u32(data, position)? as usize, position + 4)
+        } else {
+            (read_u16(data, position)? as usize, position + 2)
+        };
+        if count == 0 {
+            return Some(Self {
+                data,
+                count,
+                offset_size: 0,
+                offsets,
+            });
+        }
+        let offset_size = *data.get(offsets)? as usize;
+        if !(1..=4).contains(&offset_size) {
+            return None;
+        }
+        Some(Self {
+            data,
+            count,
+            offset_size,
+            offsets: offsets + 1,
+        })
+    }
+
+    /// Reads the offset with the given index, relative to the start of the INDEX data.
+    fn offset(&self, index: usize) -> Option<usize> {
+        let start = self.offsets + index * self.offset_size;
+        let bytes = self.data.get(start..start + self.offset_size)?;
+        let offset = bytes
+            .iter()
+            .fold(0usize, |offset, &byte| offset << 8 | byte as usize);
+        // Offsets are 1-based
+        offset.checked_sub(1)
+    }
+
+    /// Gets the position of the first byte of the INDEX data.
+    fn data_start(&self) -> usize {
+        self.offsets + (self.count + 1) * self.offset_size
+    }
+
+    /// Gets the range of the element with the given index.
+    fn get(&self, index: usize) -> Option<Range<usize>> {
+        if index >= self.count {
+            return None;
+        }
+        let start = self.data_start() + self.offset(index)?;
+        let end = self.data_start() + self.offset(index + 1)?;
+        (start <= end && end <= self.data.len()).then_some(start..end)
+    }
+
+    /// Gets the position right after the INDEX.
+    fn end(&self) -> Option<usize> {
+        if self.count == 0 {
+            return Some(self.offsets);
+        }
+        let end = self.data_start() + self.offset(self.count)?;
+        (end <= self.data.len()).then_some(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, SubsetInput};
+
+    /// Builds an INDEX with one byte offsets.
+    fn index(cff2: bool, elements: &[&[u8]]) -> Vec<u8> {
+        let mut index = if cff2 {
+            (elements.len() as u32).to_be_bytes().to_vec()
+        } else {
+            (elements.len() as u16).to_be_bytes().to_vec()
+        };
+        if elements.is_empty() {
+            return index;
+        }
+        index.push(1);
+        let mut offset = 1;
+        index.push(offset);
+        for element in elements {
+            offset += element.len() as u8;
+            index.push(offset);
+        }
+        index.extend(elements.concat());
+        index
+    }
+
+    /// Encodes a top DICT pointing to the charstrings at the given offset.
+    fn top_dict(charstrings: usize) -> Vec<u8> {
+        let mut dict = vec![29];
+        dict.extend((charstrings as u32).to_be_bytes());
+        dict.push(17);
+        dict
+    }
+
+    const CHARSTRINGS: [&[u8]; 3] = [&[14], &[], &[139, 139, 21, 14]];
+
+    #[test]
+    fn cff_charstrings_are_found() {
+        // Header, name INDEX, top DICT INDEX, string INDEX, global subroutine INDEX and charstrings
+        let mut table = vec![1, 0, 4, 1];
+        table.extend(index(false, &[b"Font"]));
+        let dict_index_len = index(false, &[&top_dict(0)]).len();
+        let charstrings = table.len() + dict_index_len + 2 + 2;
+        table.extend(index(false, &[&top_dict(charstrings)]));
+        table.extend(index(false, &[]));
+        table.extend(index(false, &[]));
+        assert_eq!(table.len(), charstrings);
+        table.extend(index(false, &CHARSTRINGS));
+
+        assert_eq!(charstrings_offset(&table, false), Some(charstrings));
+        let index = Index::parse(&table, charstrings, false).unwrap();
+        assert_eq!(index.end(), Some(table.len()));
+        for (glyph, charstring) in CHARSTRINGS.iter().enumerate() {
+            assert_eq!(&table[index.get(glyph).unwrap()], *charstring);
+        }
+        assert_eq!(index.get(3), None);
+    }
+
+    #[test]
+    fn cff2_charstrings_are_found() {
+        // Header, top DICT, global subroutine INDEX and charstrings. Operands of other operators before the offset
+        // should not confuse the parser.
+        let other_operators = [30, 0x1f, 247, 0, 12, 30];
+        let dict_len = other_operators.len() + top_dict(0).len();
+        let charstrings = 5 + dict_len + 4;
+        let mut table = vec![2, 0, 5, 0, dict_len as u8];
+        table.extend(other_operators);
+        table.extend(top_dict(charstrings));
+        table.extend(index(true, &[]));
+        assert_eq!(table.len(), charstrings);
+        table.extend(index(true, &CHARSTRINGS));
+
+        assert_eq!(charstrings_offset(&table, true), Some(charstrings));
+        let index = Index::parse(&table, charstrings, true).unwrap();
+        assert_eq!(&table[index.get(2).unwrap()], CHARSTRINGS[2]);
+        assert_eq!(index.end(), Some(table.len()));
+    }
+
+    #[test]
+    fn truncated_index_is_rejected() {
+        let mut data = index(false, &CHARSTRINGS);
+        data.pop();
+        let index = Index::parse(&data, 0, false).unwrap();
+        assert!(index.get(0).is_some());
+        assert_eq!(index.get(2), None);
+        assert_eq!(index.end(), None);
+    }
+
+    #[test]
+    fn glyf_data_survives_subsetting() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let cmap = font.nominal_glyph_mapping().unwrap();
+        assert!(font.charstrings_index().is_none());
+        assert!(font.glyph_data(GlyphId::new(u32::MAX)).is_none());
+        assert!(font.glyph_data(cmap.get(' ').unwrap()).unwrap().is_empty());
+        let original = font.glyph_data(cmap.get('a').unwrap()).unwrap();
+
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        let hinted = subset.subset_font(&font).unwrap();
+        let hinted = hinted
+            .glyph_data(hinted.nominal_glyph_mapping().unwrap().get('a').unwrap())
+            .unwrap()
+            .to_vec();
+        // Only padding may be trimmed
+        assert!(original.starts_with(&hinted));
+        assert!(original.len() - hinted.len() < 4);
+
+        subset.flags().remove_hinting();
+        let unhinted = subset.subset_font(&font).unwrap();
+        let unhinted = unhinted
+            .glyph_data(unhinted.nominal_glyph_mapping().unwrap().get('a').unwrap())
+            .unwrap();
+        assert!(unhinted.len() < hinted.len());
+    }
+}
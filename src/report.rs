@@ -0,0 +1,273 @@
+use std::fmt;
+
+use crate::{FontFace, SubsetPlan, SubsetReportError, Tag};
+
+/// Comparison between a font face and its subset.
+///
+/// The report tells how large each table was before and after subsetting, which tables were dropped, and how the
+/// glyph and codepoint counts changed. It is meant for answering the question "why is this subset still so large", and
+/// for catching size regressions in CI.
+///
+/// The [`Display`](fmt::Display) implementation renders the report as a table. With feature `serde`, the report can
+/// also be serialized, e.g. to JSON.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let mut subset = SubsetInput::new()?;
+/// subset.unicode_set().insert_range('a'..='z');
+/// let plan = subset.plan(&font)?;
+///
+/// let report = SubsetReport::from_plan(&font, &plan)?;
+/// assert!(report.subset_size < report.original_size);
+/// assert_eq!(report.subset_codepoint_count, 26);
+/// println!("{report}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SubsetReport {
+    /// Size of the tables in the original font and in the subset, ordered by tag.
+    pub tables: Vec<TableSizes>,
+    /// Size of the original font file in bytes.
+    pub original_size: usize,
+    /// Size of the subset font file in bytes.
+    pub subset_size: usize,
+    /// Number of glyphs in the original font.
+    pub original_glyph_count: usize,
+    /// Number of glyphs in the subset.
+    pub subset_glyph_count: usize,
+    /// Number of Unicode codepoints covered by the original font.
+    pub original_codepoint_count: usize,
+    /// Number of Unicode codepoints covered by the subset.
+    pub subset_codepoint_count: usize,
+    /// Number of entries in the mapping from old to new glyph ids, if the report was constructed from a
+    /// [`SubsetPlan`].
+    pub glyph_mapping_size: Option<usize>,
+}
+
+/// Size of a single table before and after subsetting. See [`SubsetReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TableSizes {
+    /// Tag of the table.
+    pub tag: Tag,
+    /// Size of the table in the original font in bytes, or [`None`] if the table did not exist.
+    pub original: Option<usize>,
+    /// Size of the table in the subset in bytes, or [`None`] if the table was dropped.
+    pub subset: Option<usize>,
+}
+
+impl TableSizes {
+    /// Tests whether the table was dropped by the subsetter.
+    pub fn is_dropped(&self) -> bool {
+        self.original.is_some() && self.subset.is_none()
+    }
+
+    /// Tests whether the table exists in the subset.
+    pub fn is_retained(&self) -> bool {
+        self.subset.is_some()
+    }
+}
+
+impl SubsetReport {
+    /// Compares the original font face with its subset.
+    pub fn new(original: &FontFace<'_>, subset: &FontFace<'_>) -> Result<Self, SubsetReportError> {
+        // Subset faces are backed by a face builder, so parse the serialized font again to see what actually gets
+        // written to the file.
+        let subset_blob = subset.underlying_blob();
+        let subset = FontFace::new(subset_blob.clone())?;
+
+        let original_tables = table_sizes(original);
+        let subset_tables = table_sizes(&subset);
+        let mut tables = original_tables
+            .iter()
+            .map(|&(tag, size)| TableSizes {
+                tag,
+                original: Some(size),
+                subset: subset_tables
+                    .iter()
+                    .find(|(t, _)| *t == tag)
+                    .map(|&(_, s)| s),
+            })
+            .collect::<Vec<_>>();
+        for &(tag, size) in &subset_tables {
+            if !original_tables.iter().any(|(t, _)| *t == tag) {
+                tables.push(TableSizes {
+                    tag,
+                    original: None,
+                    subset: Some(size),
+                });
+            }
+        }
+        tables.sort_by_key(|table| table.tag);
+
+        Ok(Self {
+            tables,
+            original_size: original.underlying_blob().len(),
+            subset_size: subset_blob.len(),
+            original_glyph_count: original.glyph_count(),
+            subset_glyph_count: subset.glyph_count(),
+            original_codepoint_count: original.covered_codepoints()?.len(),
+            subset_codepoint_count: subset.covered_codepoints()?.len(),
+            glyph_mapping_size: None,
+        })
+    }
+
+    /// Executes the plan and compares the original font face with the produced subset.
+    ///
+    /// Unlike [`SubsetReport::new`], this also records the size of the glyph mapping of the plan. If executing the plan
+    /// fails, the error is returned as [`SubsetReportError::Subsetting`].
+    pub fn from_plan(
+        original: &FontFace<'_>,
+        plan: &SubsetPlan<'_, '_>,
+    ) -> Result<Self, SubsetReportError> {
+        let subset = plan.subset()?;
+        let mut report = Self::new(original, &subset)?;
+        report.glyph_mapping_size = Some(plan.old_to_new_glyph_mapping().len());
+        Ok(report)
+    }
+
+    /// Returns the tags of tables which were dropped by the subsetter.
+    pub fn dropped_tables(&self) -> impl Iterator<Item = Tag> + '_ {
+        self.tables.iter().filter(|t| t.is_dropped()).map(|t| t.tag)
+    }
+
+    /// Returns the tags of tables which exist in the subset.
+    pub fn retained_tables(&self) -> impl Iterator<Item = Tag> + '_ {
+        self.tables
+            .iter()
+            .filter(|t| t.is_retained())
+            .map(|t| t.tag)
+    }
+}
+
+/// Lists all tables of a face with their sizes.
+fn table_sizes(font: &FontFace<'_>) -> Vec<(Tag, usize)> {
+    font.table_tags()
+        .into_iter()
+        .map(|tag| (tag, font.table(tag).len()))
+        .collect()
+}
+
+impl fmt::Display for SubsetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn size(size: Option<usize>) -> String {
+            size.map_or_else(|| "-".into(), |size| size.to_string())
+        }
+        fn change(original: Option<usize>, subset: Option<usize>) -> String {
+            match (original, subset) {
+                (Some(_), None) => "dropped".into(),
+                (None, Some(_)) => "added".into(),
+                (Some(0), Some(_)) | (None, None) => "".into(),
+                (Some(original), Some(subset)) => format!(
+                    "{:+.1}%",
+                    (subset as f64 - original as f64) * 100.0 / original as f64
+                ),
+            }
+        }
+
+        writeln!(
+            f,
+            "{:<10} {:>12} {:>12} {:>9}",
+            "Table", "Original", "Subset", "Change"
+        )?;
+        for table in &self.tables {
+            let tag: [u8; 4] = table.tag.into();
+            writeln!(
+                f,
+                "{:<10} {:>12} {:>12} {:>9}",
+                String::from_utf8_lossy(&tag),
+                size(table.original),
+                size(table.subset),
+                change(table.original, table.subset)
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<10} {:>12} {:>12} {:>9}",
+            "Total",
+            self.original_size,
+            self.subset_size,
+            change(Some(self.original_size), Some(self.subset_size))
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<10} {:>12} {:>12}",
+            "Glyphs", self.original_glyph_count, self.subset_glyph_count
+        )?;
+        writeln!(
+            f,
+            "{:<10} {:>12} {:>12}",
+            "Codepoints", self.original_codepoint_count, self.subset_codepoint_count
+        )?;
+        if let Some(glyph_mapping_size) = self.glyph_mapping_size {
+            writeln!(f, "Glyph mapping: {glyph_mapping_size} entries")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob, SubsetInput};
+
+    #[test]
+    fn report_reflects_subset() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('f');
+        subset.unicode_set().insert('i');
+        subset.drop_table_tag_set().insert(Tag::new(b"GPOS"));
+        let plan = subset.plan(&font).unwrap();
+        let report = SubsetReport::from_plan(&font, &plan).unwrap();
+
+        assert_eq!(report.original_glyph_count, 4671);
+        assert_eq!(report.subset_glyph_count, 6);
+        assert_eq!(report.original_codepoint_count, 3094);
+        assert_eq!(report.subset_codepoint_count, 2);
+        assert_eq!(report.glyph_mapping_size, Some(6));
+        assert_eq!(
+            report.original_size,
+            std::fs::read(NOTO_SANS).unwrap().len()
+        );
+        assert!(report.subset_size < report.original_size);
+
+        assert!(report.dropped_tables().any(|tag| tag == Tag::new(b"GPOS")));
+        assert!(report.retained_tables().any(|tag| tag == Tag::new(b"glyf")));
+        let glyf = report
+            .tables
+            .iter()
+            .find(|t| t.tag == Tag::new(b"glyf"))
+            .unwrap();
+        assert!(glyf.subset.unwrap() < glyf.original.unwrap());
+
+        let text = report.to_string();
+        assert!(text.contains("GPOS"));
+        assert!(text.contains("dropped"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn report_serializes_to_json() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        let new_font = subset.subset_font(&font).unwrap();
+        let report = SubsetReport::new(&font, &new_font).unwrap();
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["subset_codepoint_count"], 1);
+        assert_eq!(json["glyph_mapping_size"], serde_json::Value::Null);
+        assert!(json["tables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|table| table["tag"] == "glyf"));
+    }
+}
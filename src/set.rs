@@ -6,6 +6,31 @@
 //!
 //! Sets are used in HarfBuzz in some non-shaping APIs to query certain sets of characters or glyphs, or other integer
 //! values.
+//!
+//! # Inverted sets
+//! A set can be [inverted](Set::invert), after which it contains all values except those it contained before. This is
+//! how e.g. [`SubsetInput::keep_everything`](crate::SubsetInput::keep_everything) retains everything, and it can be
+//! used for subsetting to "everything except something":
+//! ```
+//! # use hb_subset::*;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut subset = SubsetInput::new()?;
+//! let mut features = subset.layout_feature_tag_set();
+//! // Keep all features except discretionary ligatures
+//! features.clear();
+//! features.invert();
+//! features.remove(Tag::new(b"dlig"));
+//! assert!(features.is_inverted());
+//! assert!(features.contains(Tag::new(b"smcp")));
+//! assert!(!features.contains(Tag::new(b"dlig")));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Inverted sets are well-defined everywhere, but they are huge: [`Set::len`] counts all [`u32`] values in the set, and
+//! iterating goes through all of them in order. Sets over [`char`]s and [`NameId`]s are an exception, as iterating
+//! them stops at the largest valid value. To find out which values an inverted set lacks, invert a copy of it and
+//! iterate over that instead.
 
 use std::{
     any::TypeId,
@@ -16,7 +41,7 @@ use std::{
     ops::{Bound, RangeBounds},
 };
 
-use crate::{sys, AllocationError, Tag};
+use crate::{sys, AllocationError, GlyphId, NameId, Script, Tag, UnicodeBlock};
 
 /// Set objects represent a mathematical set of integer values.
 pub struct Set<'a, T>(InnerSet, PhantomData<(&'a (), T)>);
@@ -54,6 +79,8 @@ impl<'a, T> Set<'a, T> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// For [inverted](Self::invert) sets, this is the number of [`u32`] values which are not excluded from the set.
     #[doc(alias = "hb_set_get_population")]
     pub fn len(&self) -> usize {
         (unsafe { sys::hb_set_get_population(self.as_raw()) }) as usize
@@ -77,6 +104,60 @@ impl<'a, T> Set<'a, T> {
         (unsafe { sys::hb_set_is_subset(other.as_raw(), self.as_raw()) }) != 0
     }
 
+    /// Inserts all values of `other` to `self`.
+    #[doc(alias = "hb_set_union")]
+    pub fn union(&mut self, other: &Set<'_, T>) {
+        unsafe { sys::hb_set_union(self.as_raw(), other.as_raw()) }
+    }
+
+    /// Removes all values from `self` which are not in `other`.
+    #[doc(alias = "hb_set_intersect")]
+    pub fn intersect(&mut self, other: &Set<'_, T>) {
+        unsafe { sys::hb_set_intersect(self.as_raw(), other.as_raw()) }
+    }
+
+    /// Removes all values of `other` from `self`.
+    #[doc(alias = "hb_set_subtract")]
+    pub fn subtract(&mut self, other: &Set<'_, T>) {
+        unsafe { sys::hb_set_subtract(self.as_raw(), other.as_raw()) }
+    }
+
+    /// Inverts the set, so that it contains exactly those values that it did not contain before.
+    ///
+    /// The inversion is done lazily, so this is cheap even though the resulting set contains almost all values. See
+    /// [inverted sets](self#inverted-sets) for how other operations behave on inverted sets.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::set::CharSet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// // All code points except the private use area
+    /// let mut set = CharSet::new()?;
+    /// set.insert_range('\u{E000}'..='\u{F8FF}');
+    /// set.invert();
+    /// assert!(set.contains('a'));
+    /// assert!(!set.contains('\u{E000}'));
+    ///
+    /// // Inverting a copy lists the excluded values
+    /// let mut excluded = set.clone();
+    /// excluded.invert();
+    /// assert_eq!(excluded.len(), 0x1900);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_set_invert")]
+    pub fn invert(&mut self) {
+        unsafe { sys::hb_set_invert(self.as_raw()) }
+    }
+
+    /// Tests whether the set is inverted. See [`Self::invert`].
+    ///
+    /// Clearing a set or copying a non-inverted set into it makes it non-inverted again.
+    #[doc(alias = "hb_set_is_inverted")]
+    pub fn is_inverted(&self) -> bool {
+        (unsafe { sys::hb_set_is_inverted(self.as_raw()) }) != 0
+    }
+
     /// Constructs a copy of the set with `'static` lifetime.
     #[doc(alias = "hb_set_copy")]
     pub fn clone_static(&self) -> Set<'static, T> {
@@ -150,14 +231,8 @@ where
                     upper - 1
                 }
             }
-            Bound::Unbounded => {
-                // Optimization to allow half-open intervals with character sets
-                if TypeId::of::<T>() == TypeId::of::<char>() {
-                    char::MAX as u32
-                } else {
-                    u32::MAX - 1
-                }
-            }
+            // Optimization to allow half-open intervals with character and name id sets
+            Bound::Unbounded => max_value::<T>(),
         };
         if upper < lower {
             return None;
@@ -215,9 +290,12 @@ where
 
 impl<'a, T> Set<'a, T>
 where
-    T: TryFrom<u32>,
+    T: TryFrom<u32> + 'static,
 {
     /// Constructs an iterator over the set.
+    ///
+    /// The values are iterated in ascending order. Iteration over sets of [`char`]s and [`NameId`]s stops at their
+    /// largest valid value, so even [inverted](Self::invert) sets of them can be iterated over.
     #[doc(alias = "hb_set_next")]
     #[doc(alias = "hb_set_previous")]
     pub fn iter(&self) -> Iter<'_, 'a, T> {
@@ -225,6 +303,58 @@ where
     }
 }
 
+impl CharSet<'static> {
+    /// Creates a set of all characters belonging to the given script.
+    ///
+    /// Note that many characters, like digits and punctuation, are shared between scripts and hence belong to
+    /// [`Script::COMMON`] instead. Combined with [`FontFace::covered_codepoints`], this can be used to subset a font
+    /// to all characters of a script that the font has:
+    /// ```
+    /// # use hb_subset::{*, set::CharSet};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut cyrillic = CharSet::from_script(Script::CYRILLIC)?;
+    /// cyrillic.intersect(&font.covered_codepoints()?);
+    ///
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().copy_from(&cyrillic);
+    /// let new_font = subset.subset_font(&font)?;
+    /// assert_eq!(new_font.covered_codepoints()?, cyrillic);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`FontFace::covered_codepoints`]: crate::FontFace::covered_codepoints
+    #[doc(alias = "hb_unicode_script")]
+    pub fn from_script(script: Script) -> Result<Self, AllocationError> {
+        let set = Self::new()?;
+        let mut run_start = None;
+        // Iterate one past the last code point to flush the last run
+        for codepoint in 0..=char::MAX as u32 + 1 {
+            let in_script = char::from_u32(codepoint).is_some_and(|c| Script::of(c) == script);
+            match (in_script, run_start) {
+                (true, None) => run_start = Some(codepoint),
+                (false, Some(first)) => {
+                    unsafe { sys::hb_set_add_range(set.as_raw(), first, codepoint - 1) };
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(set)
+    }
+
+    /// Creates a set of all code points allocated to the given Unicode block.
+    ///
+    /// The set contains also code points which have not (yet) been assigned to any character.
+    pub fn from_unicode_block(block: UnicodeBlock) -> Result<Self, AllocationError> {
+        let set = Self::new()?;
+        let range = block.range();
+        unsafe { sys::hb_set_add_range(set.as_raw(), *range.start(), *range.end()) };
+        Ok(set)
+    }
+}
+
 impl<'a, T> Set<'a, T> {
     /// Converts the set into raw [`sys::hb_set_t`] pointer.
     ///
@@ -277,9 +407,16 @@ impl<'a, T> Clone for Set<'a, T> {
 
 impl<'a, T> fmt::Debug for Set<'a, T>
 where
-    T: TryFrom<u32> + fmt::Debug,
+    T: TryFrom<u32> + fmt::Debug + 'static,
 {
+    /// Formats the set. Inverted sets are formatted as the values they lack, prefixed with `!`.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_inverted() {
+            let mut excluded = self.clone_static();
+            excluded.invert();
+            write!(f, "!")?;
+            return f.debug_set().entries(&excluded).finish();
+        }
         f.debug_set().entries(self).finish()
     }
 }
@@ -297,9 +434,20 @@ where
     }
 }
 
+impl<'a, T> Extend<T> for Set<'a, T>
+where
+    T: Into<u32>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
 impl<'s, 'a, T> IntoIterator for &'s Set<'a, T>
 where
-    T: TryFrom<u32>,
+    T: TryFrom<u32> + 'static,
 {
     type Item = T;
     type IntoIter = Iter<'s, 'a, T>;
@@ -340,15 +488,21 @@ impl<'s, 'a, T> FusedIterator for Iter<'s, 'a, T> where T: TryFrom<u32> {}
 /// Actual implementation for [`Iter`].
 ///
 /// This implementation does not care whether the target type can or cannot represent the target type. It just returns
-/// [`u32`], no matter what. [`Iter`] is responsible to then filter out invalid values.
+/// [`u32`], no matter what. [`Iter`] is responsible to then filter out invalid values. The only exception is that
+/// iteration over [`char`]s and [`NameId`]s is limited to their valid values, so that e.g. inverted sets of characters
+/// do not iterate over billions of invalid values.
 struct IterImpl<'s, 'a, T>(&'s Set<'a, T>, u32, u32);
 
 impl<'s, 'a, T> IterImpl<'s, 'a, T> {
     const LAST_VALUE: u32 = sys::HB_SET_VALUE_INVALID - 1;
-    fn new(set: &'s Set<'a, T>) -> Self {
+    fn new(set: &'s Set<'a, T>) -> Self
+    where
+        T: 'static,
+    {
         #[allow(clippy::assertions_on_constants, clippy::absurd_extreme_comparisons)]
         const _: () = assert!(u32::MAX == sys::HB_SET_VALUE_INVALID);
-        Self(set, sys::HB_SET_VALUE_INVALID, sys::HB_SET_VALUE_INVALID)
+        // The back of the iterator is exclusive
+        Self(set, sys::HB_SET_VALUE_INVALID, max_value::<T>() + 1)
     }
 
     fn mark_ended(&mut self) {
@@ -412,6 +566,17 @@ impl<'s, 'a, T> DoubleEndedIterator for IterImpl<'s, 'a, T> {
     }
 }
 
+/// Gets the largest value a set over `T` can meaningfully contain.
+fn max_value<T: 'static>() -> u32 {
+    if TypeId::of::<T>() == TypeId::of::<char>() {
+        char::MAX as u32
+    } else if TypeId::of::<T>() == TypeId::of::<NameId>() {
+        u16::MAX as u32
+    } else {
+        sys::HB_SET_VALUE_INVALID - 1
+    }
+}
+
 /// Implementation detail of Set to hide source reference from drop check.
 ///
 /// If the pointer was directly contained in [`Set`] with `Drop` implemented, the following code would not compile:
@@ -441,12 +606,18 @@ pub type CharSet<'a> = Set<'a, char>;
 
 /// Set over [`u32`]s, except [`u32::MAX`].
 ///
-/// Trying to insert [`u32::MAX`] will cause a panic. [`U32Set`] is commonly used to represent sets of glyph IDs.
+/// Trying to insert [`u32::MAX`] will cause a panic.
 pub type U32Set<'a> = Set<'a, u32>;
 
+/// Set over [`GlyphId`]s.
+pub type GlyphSet<'a> = Set<'a, GlyphId>;
+
 /// Set over [`Tag`]s.
 pub type TagSet<'a> = Set<'a, Tag>;
 
+/// Set over [`NameId`]s.
+pub type NameIdSet<'a> = Set<'a, NameId>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -628,6 +799,37 @@ mod tests {
         assert_eq!(b.len(), 1);
     }
 
+    #[test]
+    fn set_operations_work() {
+        let mut a = U32Set::from_iter([1, 2, 3]);
+        a.union(&U32Set::from_iter([3, 4]));
+        assert_eq!(a.iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        a.intersect(&U32Set::from_iter([2, 3, 4, 5]));
+        assert_eq!(a.iter().collect::<Vec<_>>(), [2, 3, 4]);
+        a.subtract(&U32Set::from_iter([3]));
+        assert_eq!(a.iter().collect::<Vec<_>>(), [2, 4]);
+    }
+
+    #[test]
+    fn script_set_works() {
+        let set = CharSet::from_script(Script::CYRILLIC).unwrap();
+        assert!(set.contains('ж'));
+        assert!(set.contains('Ё'));
+        assert!(!set.contains('a'));
+        assert!(!set.contains('1'));
+        assert!(set.iter().all(|c| Script::of(c) == Script::CYRILLIC));
+    }
+
+    #[test]
+    fn unicode_block_set_works() {
+        let block = UnicodeBlock::from_name("Basic Latin").unwrap();
+        let set = CharSet::from_unicode_block(block).unwrap();
+        assert_eq!(set.len(), 128);
+        assert!(set.contains('\0'));
+        assert!(set.contains('\u{7F}'));
+        assert!(!set.contains('\u{80}'));
+    }
+
     #[test]
     fn iter_works() {
         let mut set = U32Set::new().unwrap();
@@ -754,4 +956,67 @@ mod tests {
         assert_eq!(iter.next(), None);
         assert_eq!(iter.next_back(), None);
     }
+
+    #[test]
+    fn inverted_set_contains_everything_else() {
+        let mut set = U32Set::new().unwrap();
+        set.insert_range(10..20);
+        set.invert();
+        assert!(set.is_inverted());
+        assert!(!set.is_empty());
+        assert!(set.contains(0));
+        assert!(!set.contains(10));
+        assert!(set.contains(u32::MAX - 1));
+        assert_eq!(set.len(), u32::MAX as usize - 10);
+        assert_eq!(
+            format!("{set:?}"),
+            "!{10, 11, 12, 13, 14, 15, 16, 17, 18, 19}"
+        );
+        assert_eq!(
+            set.iter().take(12).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 20, 21]
+        );
+        assert_eq!(set.iter().next_back(), Some(u32::MAX - 1));
+
+        set.invert();
+        assert!(!set.is_inverted());
+        assert_eq!(set.iter().collect::<Vec<_>>(), (10..20).collect::<Vec<_>>());
+
+        set.invert();
+        set.clear();
+        assert!(!set.is_inverted());
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn inverted_char_set_iteration_stops_at_char_max() {
+        let mut set = CharSet::new().unwrap();
+        set.insert_range('\0'..='\u{10FFF0}');
+        set.invert();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            ('\u{10FFF1}'..=char::MAX).collect::<Vec<_>>()
+        );
+        assert_eq!(set.iter().next_back(), Some(char::MAX));
+        assert_eq!(set.iter().rev().nth(14), Some('\u{10FFF1}'));
+    }
+
+    #[test]
+    fn subset_input_accepts_inverted_sets() {
+        use crate::{tests::NOTO_SANS, Blob, FontFace, SubsetInput, Tag};
+
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('A');
+        let mut features = subset.layout_feature_tag_set();
+        features.clear();
+        features.invert();
+        features.remove(Tag::new(b"smcp"));
+        drop(features);
+
+        let plan = subset.plan(&font).unwrap();
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(features.contains(Tag::new(b"c2sc")));
+        assert!(!features.contains(Tag::new(b"smcp")));
+    }
 }
@@ -1,13 +1,23 @@
-use std::{marker::PhantomData, ptr::null_mut};
+use std::{cell::OnceCell, marker::PhantomData, ptr::null_mut};
 
 use crate::{
     map::Map,
-    set::{CharSet, Set, TagSet, U32Set},
-    sys, AllocationError, FontFace, SubsettingError,
+    set::{CharSet, GlyphSet, NameIdSet, Set, TagSet, U32Set},
+    sys, AllocationError, Capability, EmbeddingPolicy, FontFace, Language, NewGlyphId, OldGlyphId,
+    SubsettingError, Tag, UnsupportedError,
 };
+#[cfg(feature = "experimental")]
+use crate::{NameId, SubsetInputError};
 
+mod axes;
+mod builder;
+#[cfg(any(feature = "cache", all(feature = "isolated", unix)))]
+pub(crate) mod encoding;
 mod flags;
+mod presets;
 
+pub use axes::*;
+pub use builder::*;
 pub use flags::*;
 
 /// A description of how a font should be subset.
@@ -23,7 +33,19 @@ pub use flags::*;
 ///
 /// Fonts with graphite or AAT tables may still be subsetted but will likely need to use the retain glyph ids option and
 /// configure the subset to pass through the layout tables untouched.
-pub struct SubsetInput(*mut sys::hb_subset_input_t);
+///
+/// A subset input can also be constructed in one expression with [`SubsetInput::builder`].
+pub struct SubsetInput {
+    input: *mut sys::hb_subset_input_t,
+    /// Pinned axes, sorted by tag. These are applied to the input only once the font face is known.
+    axes: Vec<(Tag, AxisLocation)>,
+    embedding_policy: EmbeddingPolicy,
+    validate_output: bool,
+    memory_budget: Option<usize>,
+    /// Name table overrides, sorted by record. HarfBuzz has no API for reading them back, so they are tracked here.
+    #[cfg(feature = "experimental")]
+    names: Vec<(NameRecord, Option<String>)>,
+}
 
 impl SubsetInput {
     /// Creates a new subset input object.
@@ -33,7 +55,15 @@ impl SubsetInput {
         if input.is_null() {
             return Err(AllocationError);
         }
-        Ok(Self(input))
+        Ok(Self {
+            input,
+            axes: Vec::new(),
+            embedding_policy: EmbeddingPolicy::Ignore,
+            validate_output: false,
+            memory_budget: None,
+            #[cfg(feature = "experimental")]
+            names: Vec::new(),
+        })
     }
 
     /// Configures input object to keep everything in the font face. That is, all Unicodes, glyphs, names, layout items,
@@ -75,7 +105,7 @@ impl SubsetInput {
     #[doc(alias = "hb_subset_input_glyph_set")]
     #[doc(alias = "hb_subset_input_set")]
     #[doc(alias = "HB_SUBSET_SETS_GLYPH_INDEX")]
-    pub fn glyph_set(&mut self) -> U32Set<'_> {
+    pub fn glyph_set(&mut self) -> GlyphSet<'_> {
         unsafe {
             Set::from_raw(sys::hb_set_reference(sys::hb_subset_input_glyph_set(
                 self.as_raw(),
@@ -130,7 +160,7 @@ impl SubsetInput {
     /// The caller should modify the set as needed.
     #[doc(alias = "hb_subset_input_set")]
     #[doc(alias = "HB_SUBSET_SETS_NAME_ID")]
-    pub fn name_id_set(&mut self) -> U32Set<'_> {
+    pub fn name_id_set(&mut self) -> NameIdSet<'_> {
         unsafe {
             Set::from_raw(sys::hb_set_reference(sys::hb_subset_input_set(
                 self.as_raw(),
@@ -153,6 +183,36 @@ impl SubsetInput {
         }
     }
 
+    /// Retains name table records in the given languages, in addition to those already in [`Self::name_lang_id_set`].
+    ///
+    /// Both Windows and Macintosh records are retained. A language without a region covers all of its regional
+    /// variants, e.g. `de` covers German as used in both Germany and Switzerland. See
+    /// [`Language::windows_language_id`] for how languages are mapped to language ids. Languages without a known id
+    /// are ignored.
+    ///
+    /// By default, only English (United States) records are retained. To retain records only in the given languages,
+    /// clear [`Self::name_lang_id_set`] first.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.retain_name_languages(["de".parse()?, "fr-CA".parse()?]);
+    /// assert!(subset.name_lang_id_set().contains(0x0409)); // en-US, retained by default
+    /// assert!(subset.name_lang_id_set().contains(0x0807)); // de-CH
+    /// assert!(subset.name_lang_id_set().contains(0x0C0C)); // fr-CA
+    /// assert!(!subset.name_lang_id_set().contains(0x040C)); // fr-FR
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain_name_languages(&mut self, languages: impl IntoIterator<Item = Language>) {
+        let mut set = self.name_lang_id_set();
+        for language in languages {
+            set.extend(language.name_language_ids().into_iter().map(u32::from));
+        }
+    }
+
     /// Gets the set of layout feature tags that will be retained in the subset.
     ///
     /// The caller should modify the set as needed.
@@ -194,7 +254,7 @@ impl SubsetInput {
     /// Such fonts may not work for all use cases (for example ots will reject unsorted coverage tables). So it's
     /// recommended, if possible, to supply a monotonic mapping.
     #[doc(alias = "hb_subset_input_old_to_new_glyph_mapping")]
-    pub fn old_to_new_glyph_mapping(&mut self) -> Map<'_, u32, u32> {
+    pub fn old_to_new_glyph_mapping(&mut self) -> Map<'_, OldGlyphId, NewGlyphId> {
         unsafe {
             Map::from_raw(sys::hb_map_reference(
                 sys::hb_subset_input_old_to_new_glyph_mapping(self.as_raw()),
@@ -202,26 +262,254 @@ impl SubsetInput {
         }
     }
 
+    /// Overrides a record of the name table in the subset.
+    ///
+    /// The record is identified by its name id, platform id, encoding id and language id. If `value` is [`Some`], the
+    /// record is set to it, adding the record if the font does not have one. If `value` is [`None`], the record is
+    /// deleted. For the Windows platform (3), the value is encoded as UTF-16. For the Macintosh platform (1), the bytes
+    /// of the value are used as is, so only ASCII strings are reliably encoded. Values cannot be set for other
+    /// platforms, which fails with [`SubsetInputError::UnsupportedNamePlatform`], but their records can be deleted.
+    ///
+    /// Overrides are subject to filtering by [`Self::name_id_set`] and [`Self::name_lang_id_set`], so a record is only
+    /// written if its name id and language id are retained.
+    ///
+    /// This is an experimental HarfBuzz API which is only available with feature `experimental`, and only with the
+    /// bundled HarfBuzz. Fails with [`SubsetInputError::Unsupported`] when using the system HarfBuzz, see
+    /// [`Capability::NameTableOverride`].
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// # if !Capability::NameTableOverride.is_supported() { return Ok(()); }
+    /// subset.override_name_table(NameId::FONT_FAMILY, 3, 1, 0x409, Some("My Font"))?;
+    /// let new_font = subset.subset_font(&font)?;
+    /// assert_eq!(new_font.font_family(), "My Font");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "experimental")]
+    #[doc(alias = "hb_subset_input_override_name_table")]
+    pub fn override_name_table(
+        &mut self,
+        name_id: impl Into<NameId>,
+        platform_id: u16,
+        encoding_id: u16,
+        language_id: u16,
+        value: Option<&str>,
+    ) -> Result<(), SubsetInputError> {
+        let name_id = name_id.into();
+        let (ptr, len) = match value {
+            Some(_) if !matches!(platform_id, 1 | 3) => {
+                return Err(SubsetInputError::UnsupportedNamePlatform(platform_id))
+            }
+            Some(value) => (
+                value.as_ptr() as *const std::ffi::c_char,
+                value
+                    .len()
+                    .try_into()
+                    .map_err(|_| SubsetInputError::NameValueTooLong(value.len()))?,
+            ),
+            None => (std::ptr::null(), 0),
+        };
+        Capability::NameTableOverride.check()?;
+        #[cfg(not(hb_subset_override_name_table))]
+        let success = {
+            let _ = (ptr, len);
+            0
+        };
+        #[cfg(hb_subset_override_name_table)]
+        let success = unsafe {
+            sys::hb_subset_input_override_name_table(
+                self.as_raw(),
+                name_id.into(),
+                platform_id.into(),
+                encoding_id.into(),
+                language_id.into(),
+                ptr,
+                len,
+            )
+        };
+        // The arguments were checked above, so this can only fail to allocate
+        if success == 0 {
+            return Err(AllocationError.into());
+        }
+
+        let record = NameRecord {
+            name_id,
+            platform_id,
+            encoding_id,
+            language_id,
+        };
+        let value = value.map(String::from);
+        match self
+            .names
+            .binary_search_by_key(&record, |(record, _)| *record)
+        {
+            Ok(idx) => self.names[idx].1 = value,
+            Err(idx) => self.names.insert(idx, (record, value)),
+        }
+        Ok(())
+    }
+
+    /// Gets the name table overrides set with [`Self::override_name_table`], ordered by record.
+    ///
+    /// A value of [`None`] means that the record is deleted.
+    #[cfg(feature = "experimental")]
+    pub fn name_table_overrides(&self) -> &[(NameRecord, Option<String>)] {
+        &self.names
+    }
+
+    /// Sets how the embedding permissions of the font are treated.
+    ///
+    /// By default, permissions are ignored. With [`EmbeddingPolicy::Enforce`], subsetting fails with
+    /// [`SubsettingError::EmbeddingRestricted`] if the font does not allow subsetting.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.set_embedding_policy(EmbeddingPolicy::Enforce);
+    /// subset.unicode_set().insert('a');
+    /// // Noto Sans is licensed under the Open Font License, which allows subsetting
+    /// let new_font = subset.subset_font(&font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_embedding_policy(&mut self, policy: EmbeddingPolicy) {
+        self.embedding_policy = policy;
+    }
+
+    /// Gets the embedding policy set with [`Self::set_embedding_policy`].
+    pub fn embedding_policy(&self) -> EmbeddingPolicy {
+        self.embedding_policy
+    }
+
+    /// Sets whether the produced subset is validated with [`FontFace::validate`].
+    ///
+    /// If enabled, [`Self::subset_font`] fails with [`SubsettingError::InvalidOutput`] if any issues are found.
+    /// Validation is disabled by default, as it takes time proportional to the size of the subset.
+    pub fn set_validate_output(&mut self, validate: bool) {
+        self.validate_output = validate;
+    }
+
+    /// Gets whether the produced subset is validated. See [`Self::set_validate_output`].
+    pub fn validate_output(&self) -> bool {
+        self.validate_output
+    }
+
+    /// Limits the memory HarfBuzz may allocate while subsetting a font to the given number of bytes.
+    ///
+    /// The budget applies separately to each call of [`Self::subset_font`] and [`Self::plan`], and to each call of
+    /// [`SubsetPlan::subset`] on a plan created with a budget. It covers the memory allocated during the call,
+    /// including the produced subset. If HarfBuzz needs more memory, the call fails with
+    /// [`SubsettingError::MemoryBudgetExceeded`] instead of exhausting the memory of the process. This is useful when
+    /// subsetting untrusted fonts, as a malicious font can make HarfBuzz allocate heavily. There is no budget by
+    /// default.
+    ///
+    /// The budget can be enforced only with the bundled HarfBuzz built with feature `custom-allocator`, see
+    /// [`Capability::MemoryBudget`]. Otherwise subsetting with a budget fails with [`SubsettingError::Unsupported`].
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// subset.set_memory_budget(Some(1024));
+    /// # if !Capability::MemoryBudget.is_supported() { return Ok(()); }
+    /// assert!(matches!(
+    ///     subset.subset_font(&font),
+    ///     Err(SubsettingError::MemoryBudgetExceeded(1024))
+    /// ));
+    ///
+    /// subset.set_memory_budget(Some(16 * 1024 * 1024));
+    /// let new_font = subset.subset_font(&font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        self.memory_budget = budget;
+    }
+
+    /// Gets the memory budget set with [`Self::set_memory_budget`].
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    /// Checks the embedding permissions of the font against the embedding policy.
+    fn check_embedding_permissions(&self, font: &FontFace<'_>) -> Result<(), SubsettingError> {
+        if self.embedding_policy == EmbeddingPolicy::Ignore {
+            return Ok(());
+        }
+        match font.embedding_permissions() {
+            Some(permissions) if !permissions.allows_subsetting() => {
+                Err(SubsettingError::EmbeddingRestricted(permissions))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the linked HarfBuzz supports the flags the input uses.
+    ///
+    /// Pinned axes are checked by [`Self::write_pinned_axes`].
+    fn check_capabilities(&self) -> Result<(), UnsupportedError> {
+        let flags = Flags(unsafe { sys::hb_subset_input_get_flags(self.as_raw()) });
+        if flags.skips_layout_closure() {
+            Capability::NoLayoutClosure.check()?;
+        }
+        Ok(())
+    }
+
     /// Subsets a font according to provided input.
+    ///
+    /// Fails with [`SubsettingError::Unsupported`] if the input uses a [`Capability`] which the linked HarfBuzz does
+    /// not support. Pinned axes are written to the raw subset input first, see [`Self::apply_pinned_axes`].
     #[doc(alias = "hb_subset_or_fail")]
     pub fn subset_font(&self, font: &FontFace<'_>) -> Result<FontFace<'static>, SubsettingError> {
-        let face = unsafe { sys::hb_subset_or_fail(font.as_raw(), self.as_raw()) };
-        if face.is_null() {
-            return Err(SubsettingError);
+        self.check_capabilities()?;
+        self.check_embedding_permissions(font)?;
+        let face = within_memory_budget(self.memory_budget, || {
+            self.write_pinned_axes(font)?;
+            let face = unsafe { sys::hb_subset_or_fail(font.as_raw(), self.as_raw()) };
+            if face.is_null() {
+                return Err(SubsettingError::Failed);
+            }
+            Ok(unsafe { FontFace::from_raw(face) })
+        })?;
+        if self.validate_output {
+            let issues = face.validate();
+            if !issues.is_empty() {
+                return Err(SubsettingError::InvalidOutput(issues));
+            }
         }
-        Ok(unsafe { FontFace::from_raw(face) })
+        Ok(face)
     }
 
     /// Computes a plan for subsetting the supplied face according to a provided input.
     ///
-    /// The plan describes which tables and glyphs should be retained.
+    /// The plan describes which tables and glyphs should be retained. Pinned axes are written to the raw subset input
+    /// first, see [`Self::apply_pinned_axes`]. The memory budget of the input also applies to executing the plan.
     #[doc(alias = "hb_subset_plan_create_or_fail")]
     pub fn plan<'f>(&self, font: &'f FontFace<'_>) -> Result<SubsetPlan<'f, '_>, SubsettingError> {
-        let plan = unsafe { sys::hb_subset_plan_create_or_fail(font.as_raw(), self.as_raw()) };
-        if plan.is_null() {
-            return Err(SubsettingError);
-        }
-        Ok(unsafe { SubsetPlan::from_raw(plan) })
+        self.check_capabilities()?;
+        self.check_embedding_permissions(font)?;
+        let mut plan = within_memory_budget(self.memory_budget, || {
+            self.write_pinned_axes(font)?;
+            let plan = unsafe { sys::hb_subset_plan_create_or_fail(font.as_raw(), self.as_raw()) };
+            if plan.is_null() {
+                return Err(SubsettingError::Failed);
+            }
+            Ok(unsafe { SubsetPlan::from_raw(plan) })
+        })?;
+        plan.memory_budget = self.memory_budget;
+        Ok(plan)
     }
 }
 
@@ -231,9 +519,13 @@ impl SubsetInput {
     /// This method transfers the ownership of the subset input to the caller. It is up to the caller to call
     /// [`sys::hb_subset_input_destroy`] to free the pointer, or call [`Self::from_raw`] to convert it back into
     /// [`SubsetInput`].
-    pub fn into_raw(self) -> *mut sys::hb_subset_input_t {
-        let ptr = self.0;
-        std::mem::forget(self);
+    ///
+    /// Axes pinned with [`Self::pin_axis`] are stored in the raw subset input only once they have been resolved against
+    /// a font, so call [`Self::apply_pinned_axes`] first to keep them. Name table overrides are kept in the raw subset
+    /// input, but they are no longer tracked by the Rust side.
+    pub fn into_raw(mut self) -> *mut sys::hb_subset_input_t {
+        let ptr = self.input;
+        self.input = null_mut();
         ptr
     }
 
@@ -241,7 +533,7 @@ impl SubsetInput {
     ///
     /// Unlike [`Self::into_raw`], this method does not transfer the ownership of the pointer to the caller.
     pub fn as_raw(&self) -> *mut sys::hb_subset_input_t {
-        self.0
+        self.input
     }
 
     /// Constructs a subset input from raw [`sys::hb_subset_input_t`] pointer.
@@ -250,57 +542,206 @@ impl SubsetInput {
     /// The given `subset` pointer must either be constructed by some Harfbuzz function, or be returned from
     /// [`Self::into_raw`].
     pub unsafe fn from_raw(subset: *mut sys::hb_subset_input_t) -> Self {
-        Self(subset)
+        Self {
+            input: subset,
+            axes: Vec::new(),
+            embedding_policy: EmbeddingPolicy::Ignore,
+            validate_output: false,
+            memory_budget: None,
+            #[cfg(feature = "experimental")]
+            names: Vec::new(),
+        }
+    }
+}
+
+/// Runs the given function, which calls HarfBuzz, within the memory budget, if any.
+fn within_memory_budget<T>(
+    budget: Option<usize>,
+    f: impl FnOnce() -> Result<T, SubsettingError>,
+) -> Result<T, SubsettingError> {
+    let Some(budget) = budget else {
+        return f();
+    };
+    Capability::MemoryBudget.check()?;
+    #[cfg(hb_subset_rust_allocator)]
+    {
+        let (result, exceeded) = crate::allocator::with_memory_budget(budget, f);
+        if exceeded {
+            // HarfBuzz may have worked around the failed allocations, but the result cannot be trusted
+            return Err(SubsettingError::MemoryBudgetExceeded(budget));
+        }
+        result
+    }
+    #[cfg(not(hb_subset_rust_allocator))]
+    {
+        let _ = budget;
+        f()
     }
 }
 
 impl Drop for SubsetInput {
     #[doc(alias = "hb_subset_input_destroy")]
     fn drop(&mut self) {
-        unsafe { sys::hb_subset_input_destroy(self.0) }
+        if !self.input.is_null() {
+            unsafe { sys::hb_subset_input_destroy(self.input) }
+        }
     }
 }
 
+/// Identifies a single record of the name table. See [`SubsetInput::override_name_table`].
+#[cfg(feature = "experimental")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NameRecord {
+    /// Name id of the record, e.g. [`NameId::FONT_FAMILY`].
+    pub name_id: NameId,
+    /// Platform id of the record, e.g. 3 for Windows.
+    pub platform_id: u16,
+    /// Platform-specific encoding id of the record, e.g. 1 for Unicode BMP on Windows.
+    pub encoding_id: u16,
+    /// Platform-specific language id of the record, e.g. 0x409 for English (United States) on Windows.
+    pub language_id: u16,
+}
+
 /// Information about how a subsetting operation will be executed.
 ///
 /// This includes e.g. how glyph ids are mapped from the original font to the subset.
 pub struct SubsetPlan<'f, 'b> {
     plan: *mut sys::hb_subset_plan_t,
     // The lifetime here is actually referring to the lifetime of SubsetPlan
-    unicode_to_old_glyph_mapping: Map<'static, char, u32>,
-    new_to_old_glyph_mapping: Map<'static, u32, u32>,
-    old_to_new_glyph_mapping: Map<'static, u32, u32>,
+    unicode_to_old_glyph_mapping: Map<'static, char, OldGlyphId>,
+    new_to_old_glyph_mapping: Map<'static, NewGlyphId, OldGlyphId>,
+    old_to_new_glyph_mapping: Map<'static, OldGlyphId, NewGlyphId>,
+    /// Layout feature and script tags of the subset, computed on first use.
+    layout_tags: OnceCell<(TagSet<'static>, TagSet<'static>)>,
+    /// Memory budget of the subset input the plan was created from.
+    memory_budget: Option<usize>,
     _font: PhantomData<&'f FontFace<'b>>,
 }
 
 impl<'f, 'b> SubsetPlan<'f, 'b> {
     /// Executes the subsetting plan.
+    ///
+    /// If the plan was created with a memory budget, see [`SubsetInput::set_memory_budget`], executing it is limited
+    /// to the same budget.
     #[doc(alias = "hb_subset_plan_execute_or_fail")]
     pub fn subset(&self) -> Result<FontFace<'b>, SubsettingError> {
-        let font = unsafe { sys::hb_subset_plan_execute_or_fail(self.as_raw()) };
-        if font.is_null() {
-            return Err(SubsettingError);
-        }
-        Ok(unsafe { FontFace::from_raw(font) })
+        within_memory_budget(self.memory_budget, || {
+            let font = unsafe { sys::hb_subset_plan_execute_or_fail(self.as_raw()) };
+            if font.is_null() {
+                return Err(SubsettingError::Failed);
+            }
+            Ok(unsafe { FontFace::from_raw(font) })
+        })
     }
 
     /// Returns the mapping between codepoints in the original font and the associated glyph id in the original font.
     #[doc(alias = "hb_subset_plan_unicode_to_old_glyph_mapping")]
-    pub fn unicode_to_old_glyph_mapping(&self) -> &'_ Map<'_, char, u32> {
+    pub fn unicode_to_old_glyph_mapping(&self) -> &'_ Map<'_, char, OldGlyphId> {
         &self.unicode_to_old_glyph_mapping
     }
 
     /// Returns the mapping between glyphs in the subset that will be produced by plan and the glyph in the original font.
     #[doc(alias = "hb_subset_plan_new_to_old_glyph_mapping")]
-    pub fn new_to_old_glyph_mapping(&self) -> &'_ Map<'_, u32, u32> {
+    pub fn new_to_old_glyph_mapping(&self) -> &'_ Map<'_, NewGlyphId, OldGlyphId> {
         &self.new_to_old_glyph_mapping
     }
 
     /// Returns the mapping between glyphs in the original font to glyphs in the subset that will be produced by plan.
     #[doc(alias = "hb_subset_plan_old_to_new_glyph_mapping")]
-    pub fn old_to_new_glyph_mapping(&self) -> &'_ Map<'_, u32, u32> {
+    pub fn old_to_new_glyph_mapping(&self) -> &'_ Map<'_, OldGlyphId, NewGlyphId> {
         &self.old_to_new_glyph_mapping
     }
+
+    /// Returns the set of glyphs of the original font that will be retained in the subset.
+    ///
+    /// This is the final set after computing the closure of the input over `GSUB`, `COLR`, composite glyphs and so
+    /// on, so it can be used for checking what a subset would contain without producing it.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('f');
+    /// subset.unicode_set().insert('i');
+    /// let plan = subset.plan(&font)?;
+    /// // .notdef, f, i and the ligatures formed from them
+    /// assert_eq!(plan.glyphset()?.len(), 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn glyphset(&self) -> Result<Set<'static, OldGlyphId>, AllocationError> {
+        self.old_to_new_glyph_mapping.keys()
+    }
+
+    /// Returns the mapping between codepoints and the associated glyph id in the subset that will be produced by plan.
+    pub fn unicode_to_new_glyph_mapping(
+        &self,
+    ) -> Result<Map<'static, char, NewGlyphId>, AllocationError> {
+        let mut mapping = Map::new()?;
+        for (c, old) in &self.unicode_to_old_glyph_mapping {
+            if let Some(new) = self.old_to_new_glyph_mapping.get(old) {
+                mapping.insert(c, new);
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Returns the set of layout feature tags that are retained in the subset.
+    ///
+    /// These are the features of the `GSUB` and `GPOS` tables of the subset, so they reflect all pruning HarfBuzz does:
+    /// features which were not requested in [`SubsetInput::layout_feature_tag_set`] are dropped, and so are features
+    /// whose lookups the layout closure found to not apply to any retained glyph. HarfBuzz computes the pruned
+    /// features only while producing the subset, so the plan is executed with [`Self::subset`] on the first call of
+    /// this or [`Self::layout_script_tag_set`].
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('f');
+    /// subset.unicode_set().insert('i');
+    /// subset.layout_feature_tag_set().insert(Tag::new(b"c2sc"));
+    /// let plan = subset.plan(&font)?;
+    /// let features = plan.layout_feature_tag_set()?;
+    /// assert!(features.contains(Tag::new(b"liga")));
+    /// // Capitals to small capitals does not apply to lowercase letters
+    /// assert!(!features.contains(Tag::new(b"c2sc")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn layout_feature_tag_set(&self) -> Result<&TagSet<'static>, SubsettingError> {
+        Ok(&self.layout_tags()?.0)
+    }
+
+    /// Returns the set of layout script tags that are retained in the subset.
+    ///
+    /// See [`Self::layout_feature_tag_set`].
+    pub fn layout_script_tag_set(&self) -> Result<&TagSet<'static>, SubsettingError> {
+        Ok(&self.layout_tags()?.1)
+    }
+
+    /// Reads the layout feature and script tags of the subset, executing the plan the first time.
+    fn layout_tags(&self) -> Result<&(TagSet<'static>, TagSet<'static>), SubsettingError> {
+        if let Some(tags) = self.layout_tags.get() {
+            return Ok(tags);
+        }
+        // Subset faces are backed by a face builder, so parse the serialized font to read its layout tables
+        let subset = self.subset()?;
+        let subset_blob = subset.underlying_blob();
+        let subset = FontFace::new(subset_blob.clone()).map_err(|_| SubsettingError::Failed)?;
+        let (mut features, mut scripts) = TagSet::new()
+            .and_then(|features| Ok((features, TagSet::new()?)))
+            .map_err(|_| SubsettingError::Failed)?;
+        for table in [Tag::new(b"GSUB"), Tag::new(b"GPOS")] {
+            features.extend(subset.layout_feature_tags(table));
+            scripts.extend(subset.layout_script_tags(table));
+        }
+        Ok(self.layout_tags.get_or_init(|| (features, scripts)))
+    }
 }
 
 impl<'f, 'b> SubsetPlan<'f, 'b> {
@@ -349,6 +790,8 @@ impl<'f, 'b> SubsetPlan<'f, 'b> {
             unicode_to_old_glyph_mapping,
             new_to_old_glyph_mapping,
             old_to_new_glyph_mapping,
+            layout_tags: OnceCell::new(),
+            memory_budget: None,
             _font: PhantomData,
         }
     }
@@ -366,7 +809,7 @@ impl<'f, 'b> Drop for SubsetPlan<'f, 'b> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{tests::NOTO_SANS, Blob};
+    use crate::{tests::NOTO_SANS, Blob, GlyphId};
 
     #[test]
     fn keep_everything_should_keep_all_codepoints_and_glyphs() {
@@ -404,10 +847,10 @@ mod tests {
         let mut subset = SubsetInput::new().unwrap();
         subset
             .old_to_new_glyph_mapping()
-            .insert(char_to_glyph.get('a').unwrap(), 5);
+            .insert(char_to_glyph.get('a').unwrap().into(), NewGlyphId::new(5));
         subset
             .old_to_new_glyph_mapping()
-            .insert(char_to_glyph.get('b').unwrap(), 709);
+            .insert(char_to_glyph.get('b').unwrap().into(), NewGlyphId::new(709));
         subset.unicode_set().insert('a');
         subset.unicode_set().insert('b');
 
@@ -417,8 +860,191 @@ mod tests {
 
         let char_to_glyph = font.nominal_glyph_mapping().unwrap();
         // But the specified ones should be what we set
-        assert_eq!(char_to_glyph.get('a').unwrap(), 5);
-        assert_eq!(char_to_glyph.get('b').unwrap(), 709);
+        assert_eq!(char_to_glyph.get('a').unwrap(), GlyphId::new(5));
+        assert_eq!(char_to_glyph.get('b').unwrap(), GlyphId::new(709));
+    }
+
+    #[test]
+    fn plan_describes_subset() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('f');
+        subset.unicode_set().insert('i');
+        let plan = subset.plan(&font).unwrap();
+
+        let glyphset = plan.glyphset().unwrap();
+        assert_eq!(glyphset.len(), 6);
+        assert!(glyphset.contains(OldGlyphId::new(0)));
+
+        let new_font = subset.subset_font(&font).unwrap();
+        let nominal = new_font.nominal_glyph_mapping().unwrap();
+        let unicode_to_new = plan.unicode_to_new_glyph_mapping().unwrap();
+        assert_eq!(unicode_to_new.len(), 2);
+        for c in ['f', 'i'] {
+            assert_eq!(unicode_to_new.get(c).map(GlyphId::from), nominal.get(c));
+        }
+
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(features.contains(Tag::new(b"liga")));
+        assert!(!features.contains(Tag::new(b"smcp")));
+        assert!(plan
+            .layout_script_tag_set()
+            .unwrap()
+            .contains(Tag::new(b"latn")));
+        drop(plan);
+
+        // Requested features which do not apply to the retained glyphs are pruned
+        subset.layout_feature_tag_set().insert(Tag::new(b"c2sc"));
+        let plan = subset.plan(&font).unwrap();
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(!features.contains(Tag::new(b"c2sc")));
+        drop(plan);
+
+        subset.drop_table_tag_set().insert(Tag::new(b"GSUB"));
+        let plan = subset.plan(&font).unwrap();
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(!features.contains(Tag::new(b"liga")));
+        let gpos_features = font.layout_feature_tags(Tag::new(b"GPOS"));
+        assert!(features
+            .iter()
+            .all(|feature| gpos_features.contains(&feature)));
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn overriding_name_table_renames_font() {
+        if !Capability::NameTableOverride.is_supported() {
+            return;
+        }
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        subset
+            .override_name_table(NameId::FONT_FAMILY, 3, 1, 0x409, Some("Renamed"))
+            .unwrap();
+        subset
+            .override_name_table(NameId::FONT_SUBFAMILY, 3, 1, 0x409, None)
+            .unwrap();
+        // Overrides for languages that are filtered out have no effect
+        subset
+            .override_name_table(NameId::FULL_NAME, 3, 1, 0x40c, Some("Renommé"))
+            .unwrap();
+        assert_eq!(subset.name_table_overrides().len(), 3);
+
+        let new_font = subset.subset_font(&font).unwrap();
+        assert_eq!(new_font.font_family(), "Renamed");
+        assert_eq!(new_font.font_subfamily(), "");
+        assert_eq!(new_font.full_name(), font.full_name());
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn name_overrides_need_capability() {
+        let mut subset = SubsetInput::new().unwrap();
+        let result = subset.override_name_table(NameId::FONT_FAMILY, 3, 1, 0x409, Some("Renamed"));
+        if Capability::NameTableOverride.is_supported() {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(result, Err(SubsetInputError::Unsupported(_))));
+        }
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn name_overrides_reject_unsupported_platforms() {
+        let mut subset = SubsetInput::new().unwrap();
+        assert!(matches!(
+            subset.override_name_table(NameId::FONT_FAMILY, 0, 3, 0, Some("Unicode")),
+            Err(SubsetInputError::UnsupportedNamePlatform(0))
+        ));
+        assert!(matches!(
+            subset.override_name_table(NameId::FONT_FAMILY, 4, 0, 0, Some("Custom")),
+            Err(SubsetInputError::UnsupportedNamePlatform(4))
+        ));
+        assert!(subset.name_table_overrides().is_empty());
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn name_overrides_respect_name_id_set() {
+        if !Capability::NameTableOverride.is_supported() {
+            return;
+        }
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        subset
+            .override_name_table(NameId::FONT_FAMILY, 3, 1, 0x409, Some("Renamed"))
+            .unwrap();
+        subset
+            .override_name_table(NameId::FONT_FAMILY, 3, 1, 0x409, Some("Renamed again"))
+            .unwrap();
+        assert_eq!(subset.name_table_overrides().len(), 1);
+        subset.name_id_set().remove(NameId::FONT_FAMILY);
+
+        let new_font = subset.subset_font(&font).unwrap();
+        assert_eq!(new_font.font_family(), "");
+
+        subset.name_id_set().insert(NameId::FONT_FAMILY);
+        let new_font = subset.subset_font(&font).unwrap();
+        assert_eq!(new_font.font_family(), "Renamed again");
+    }
+
+    /// Reads Noto Sans and sets the `fsType` field of its OS/2 table.
+    fn noto_sans_with_fs_type(fs_type: u16) -> Vec<u8> {
+        let mut data = std::fs::read(NOTO_SANS).unwrap();
+        let num_tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+        let record = (0..num_tables)
+            .map(|i| 12 + 16 * i)
+            .find(|&record| &data[record..record + 4] == b"OS/2")
+            .unwrap();
+        let offset = u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()) as usize;
+        data[offset + 8..offset + 10].copy_from_slice(&fs_type.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn embedding_policy_is_enforced() {
+        let restricted = noto_sans_with_fs_type(0x0002);
+        let font = FontFace::new(Blob::from_bytes(&restricted).unwrap()).unwrap();
+        assert!(font.embedding_permissions().unwrap().is_restricted());
+
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        assert!(subset.subset_font(&font).is_ok());
+
+        subset.set_embedding_policy(EmbeddingPolicy::Enforce);
+        assert!(matches!(
+            subset.subset_font(&font),
+            Err(SubsettingError::EmbeddingRestricted(permissions)) if permissions.is_restricted()
+        ));
+        assert!(matches!(
+            subset.plan(&font),
+            Err(SubsettingError::EmbeddingRestricted(_))
+        ));
+
+        let no_subsetting = noto_sans_with_fs_type(0x0100);
+        assert!(matches!(
+            crate::subset_with_policy(&no_subsetting, "a".chars(), EmbeddingPolicy::Enforce),
+            Err(SubsettingError::EmbeddingRestricted(permissions)) if permissions.is_no_subsetting()
+        ));
+        assert!(crate::subset(&no_subsetting, "a".chars()).is_ok());
+
+        let editable = noto_sans_with_fs_type(0x0008);
+        assert!(
+            crate::subset_with_policy(&editable, "a".chars(), EmbeddingPolicy::Enforce).is_ok()
+        );
+    }
+
+    #[test]
+    fn validating_output_accepts_valid_subset() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert_range('a'..='z');
+        subset.set_validate_output(true);
+        assert!(subset.validate_output());
+        let new_font = subset.subset_font(&font).unwrap();
+        assert_eq!(new_font.validate(), []);
     }
 
     #[test]
@@ -436,6 +1062,35 @@ mod tests {
         let plan = subset.plan(&font).unwrap();
         let plan_ptr = plan.into_raw();
         let plan = unsafe { SubsetPlan::from_raw(plan_ptr) };
+        assert!(plan
+            .layout_script_tag_set()
+            .unwrap()
+            .contains(Tag::new(b"latn")));
         drop(plan);
     }
+
+    #[test]
+    fn executing_plan_is_within_memory_budget() {
+        if !Capability::MemoryBudget.is_supported() {
+            return;
+        }
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        subset.set_memory_budget(Some(16 * 1024 * 1024));
+        let mut plan = subset.plan(&font).unwrap();
+        assert!(plan.subset().is_ok());
+
+        plan.memory_budget = Some(1024);
+        assert!(matches!(
+            plan.subset(),
+            Err(SubsettingError::MemoryBudgetExceeded(1024))
+        ));
+        assert!(matches!(
+            crate::SubsetReport::from_plan(&font, &plan),
+            Err(crate::SubsetReportError::Subsetting(
+                SubsettingError::MemoryBudgetExceeded(1024)
+            ))
+        ));
+    }
 }
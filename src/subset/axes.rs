@@ -0,0 +1,144 @@
+#[cfg(not(hb_subset_lean))]
+use crate::sys;
+use crate::{Capability, FontFace, SubsetInput, SubsettingError, Tag};
+
+/// Location of a pinned variation axis. See [`SubsetInput::pin_axis`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisLocation {
+    /// The default location of the axis, as defined by the font.
+    Default,
+    /// A fixed location in design-space coordinates.
+    Value(f32),
+}
+
+impl SubsetInput {
+    /// Pins a variation axis to its default location.
+    ///
+    /// Pinning removes the axis from the variation space of the subset. If all axes of a variable font are pinned, the
+    /// subset will be a static font instance. The pin is applied when the font is subset, and subsetting fails if the
+    /// font does not have the axis. See [`Self::apply_pinned_axes`] for how pins end up in the raw subset input.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans-Variable.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// subset.pin_axis_to_default(Tag::new(b"wght"));
+    /// let regular_a = subset.subset_font(&font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hb_subset_input_pin_axis_to_default")]
+    pub fn pin_axis_to_default(&mut self, axis: Tag) {
+        self.pin_axis(axis, AxisLocation::Default);
+    }
+
+    /// Pins a variation axis to a fixed location.
+    ///
+    /// The value is given in design-space coordinates, and it is clamped to the range of the axis. See
+    /// [`Self::pin_axis_to_default`] for details.
+    #[doc(alias = "hb_subset_input_pin_axis_location")]
+    pub fn pin_axis_location(&mut self, axis: Tag, value: f32) {
+        self.pin_axis(axis, AxisLocation::Value(value));
+    }
+
+    /// Pins a variation axis to the given location, replacing any earlier pin of the same axis.
+    pub fn pin_axis(&mut self, axis: Tag, location: AxisLocation) {
+        match self.axes.binary_search_by_key(&axis, |&(tag, _)| tag) {
+            Ok(idx) => self.axes[idx].1 = location,
+            Err(idx) => self.axes.insert(idx, (axis, location)),
+        }
+    }
+
+    /// Gets the axes pinned with [`Self::pin_axis`], ordered by tag.
+    pub fn pinned_axes(&self) -> &[(Tag, AxisLocation)] {
+        &self.axes
+    }
+
+    /// Writes the pinned axes to the raw subset input, resolving them against the given font.
+    ///
+    /// HarfBuzz needs the font face to resolve axis locations, so pins are kept on the Rust side until a font is known.
+    /// [`Self::subset_font`] and [`Self::plan`] write them to the raw subset input on every call, replacing the pins
+    /// resolved for any earlier font. Call this before [`Self::into_raw`] to keep the pins in the raw subset input.
+    pub fn apply_pinned_axes(&mut self, font: &FontFace<'_>) -> Result<(), SubsettingError> {
+        self.write_pinned_axes(font)
+    }
+
+    /// Writes the pinned axes to the raw subset input, like [`Self::apply_pinned_axes`].
+    ///
+    /// This modifies the raw subset input through a shared reference. That is sound, as [`SubsetInput`] is not
+    /// [`Sync`], and the only observable effect is on [`Self::as_raw`].
+    pub(super) fn write_pinned_axes(&self, font: &FontFace<'_>) -> Result<(), SubsettingError> {
+        if self.axes.is_empty() {
+            return Ok(());
+        }
+        Capability::AxisPinning.check()?;
+        #[cfg(hb_subset_lean)]
+        let _ = font;
+        #[cfg(not(hb_subset_lean))]
+        for &(axis, location) in &self.axes {
+            let success = match location {
+                AxisLocation::Default => unsafe {
+                    sys::hb_subset_input_pin_axis_to_default(
+                        self.as_raw(),
+                        font.as_raw(),
+                        axis.into(),
+                    )
+                },
+                AxisLocation::Value(value) => unsafe {
+                    sys::hb_subset_input_pin_axis_location(
+                        self.as_raw(),
+                        font.as_raw(),
+                        axis.into(),
+                        value,
+                    )
+                },
+            };
+            if success == 0 {
+                return Err(SubsettingError::Failed);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS_VARIABLE, Blob};
+
+    #[test]
+    fn pinning_axes_instances_font() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS_VARIABLE).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert('a');
+        subset.pin_axis_location(Tag::new(b"wght"), 300.0);
+        subset.pin_axis_location(Tag::new(b"wght"), 700.0);
+        assert_eq!(
+            subset.pinned_axes(),
+            [(Tag::new(b"wght"), AxisLocation::Value(700.0))]
+        );
+        let bold = subset.subset_font(&font).unwrap();
+        subset.pin_axis_to_default(Tag::new(b"wght"));
+        let regular = subset.subset_font(&font).unwrap();
+        assert_ne!(&*bold.underlying_blob(), &*regular.underlying_blob());
+
+        // Pins survive the conversion into a raw subset input only once applied
+        subset.apply_pinned_axes(&font).unwrap();
+        let raw = unsafe { SubsetInput::from_raw(subset.into_raw()) };
+        assert!(raw.pinned_axes().is_empty());
+        let raw_regular = raw.subset_font(&font).unwrap();
+        assert_eq!(&*raw_regular.underlying_blob(), &*regular.underlying_blob());
+    }
+
+    #[test]
+    fn pinning_missing_axis_fails() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS_VARIABLE).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.pin_axis_to_default(Tag::new(b"XXXX"));
+        assert!(subset.subset_font(&font).is_err());
+        assert!(subset.apply_pinned_axes(&font).is_err());
+    }
+}
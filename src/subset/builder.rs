@@ -0,0 +1,269 @@
+use crate::{
+    AxisLocation, Capability, EmbeddingPolicy, Flags, GlyphId, Language, SubsetInput,
+    SubsetInputError, Tag,
+};
+
+/// A builder for constructing a [`SubsetInput`] in one expression.
+///
+/// Configuring [`SubsetInput`] directly requires taking borrowed views of it one at a time, e.g. first
+/// [`SubsetInput::unicode_set`] and then [`SubsetInput::flags`]. The builder instead collects the whole configuration
+/// and applies it at once in [`Self::build`], which also checks that the configuration makes sense. A builder can be
+/// built any number of times.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+/// let subset = SubsetInput::builder()
+///     .text("Hello, world!")
+///     .drop_tables([Tag::new(b"GPOS")])
+///     .keep_features([Tag::new(b"smcp")])
+///     .flags(|flags| flags.remove_hinting().retain_glyph_names())
+///     .build()?;
+/// let new_font = subset.subset_font(&font)?;
+/// assert_eq!(new_font.covered_codepoints()?.len(), 10);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct SubsetInputBuilder {
+    unicodes: Vec<char>,
+    glyphs: Vec<GlyphId>,
+    drop_tables: Vec<Tag>,
+    keep_features: Vec<Tag>,
+    name_lang_ids: Vec<u16>,
+    flags: Flags,
+    axes: Vec<(Tag, AxisLocation)>,
+    embedding_policy: EmbeddingPolicy,
+    validate_output: bool,
+    memory_budget: Option<usize>,
+}
+
+impl SubsetInputBuilder {
+    /// Creates a builder for an empty subset input with default flags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retains all characters of the given text.
+    pub fn text(self, text: &str) -> Self {
+        self.unicodes(text.chars())
+    }
+
+    /// Retains the given characters. See [`SubsetInput::unicode_set`].
+    pub fn unicodes(mut self, unicodes: impl IntoIterator<Item = char>) -> Self {
+        self.unicodes.extend(unicodes);
+        self
+    }
+
+    /// Retains the given glyphs. See [`SubsetInput::glyph_set`].
+    pub fn glyphs(mut self, glyphs: impl IntoIterator<Item = GlyphId>) -> Self {
+        self.glyphs.extend(glyphs);
+        self
+    }
+
+    /// Drops the given tables from the subset. See [`SubsetInput::drop_table_tag_set`].
+    pub fn drop_tables(mut self, tables: impl IntoIterator<Item = Tag>) -> Self {
+        self.drop_tables.extend(tables);
+        self
+    }
+
+    /// Retains the given layout features in addition to the default ones. See [`SubsetInput::layout_feature_tag_set`].
+    pub fn keep_features(mut self, features: impl IntoIterator<Item = Tag>) -> Self {
+        self.keep_features.extend(features);
+        self
+    }
+
+    /// Retains name table records in the given languages. See [`SubsetInput::retain_name_languages`].
+    pub fn name_languages(mut self, languages: impl IntoIterator<Item = Language>) -> Self {
+        self.name_lang_ids.extend(
+            languages
+                .into_iter()
+                .flat_map(|language| language.name_language_ids()),
+        );
+        self
+    }
+
+    /// Modifies the flags of the subset input.
+    ///
+    /// The flags start from [`Flags::default`], and successive calls modify the same flags.
+    pub fn flags(mut self, f: impl FnOnce(&mut Flags) -> &mut Flags) -> Self {
+        f(&mut self.flags);
+        self
+    }
+
+    /// Pins a variation axis to the given location. See [`SubsetInput::pin_axis`].
+    ///
+    /// Pinning the same axis to two different locations makes [`Self::build`] fail.
+    pub fn pin_axis(mut self, axis: Tag, location: AxisLocation) -> Self {
+        self.axes.push((axis, location));
+        self
+    }
+
+    /// Sets the embedding policy. See [`SubsetInput::set_embedding_policy`].
+    pub fn embedding_policy(mut self, policy: EmbeddingPolicy) -> Self {
+        self.embedding_policy = policy;
+        self
+    }
+
+    /// Sets whether the produced subset is validated. See [`SubsetInput::set_validate_output`].
+    pub fn validate_output(mut self, validate: bool) -> Self {
+        self.validate_output = validate;
+        self
+    }
+
+    /// Limits the memory subsetting may use to the given number of bytes. See [`SubsetInput::set_memory_budget`].
+    pub fn memory_budget(mut self, budget: usize) -> Self {
+        self.memory_budget = Some(budget);
+        self
+    }
+
+    /// Checks the configuration and constructs a subset input from it.
+    ///
+    /// Fails with [`SubsetInputError::Unsupported`] if the configuration uses a [`Capability`] which the linked
+    /// HarfBuzz does not support.
+    pub fn build(&self) -> Result<SubsetInput, SubsetInputError> {
+        if !self.axes.is_empty() {
+            Capability::AxisPinning.check()?;
+        }
+        if self.flags.skips_layout_closure() {
+            Capability::NoLayoutClosure.check()?;
+        }
+        if self.memory_budget.is_some() {
+            Capability::MemoryBudget.check()?;
+        }
+        for &(axis, location) in &self.axes {
+            if let AxisLocation::Value(value) = location {
+                if !value.is_finite() {
+                    return Err(SubsetInputError::InvalidAxisLocation { axis, value });
+                }
+            }
+            if self
+                .axes
+                .iter()
+                .any(|&(other, other_location)| other == axis && other_location != location)
+            {
+                return Err(SubsetInputError::ConflictingAxisLocations(axis));
+            }
+        }
+        if let Some(&glyph) = self.glyphs.iter().find(|glyph| glyph.to_u32() == u32::MAX) {
+            return Err(SubsetInputError::InvalidGlyph(glyph));
+        }
+
+        let mut input = SubsetInput::new()?;
+        *input.flags() = self.flags;
+        let mut unicodes = input.unicode_set();
+        for &unicode in &self.unicodes {
+            unicodes.insert(unicode);
+        }
+        drop(unicodes);
+        let mut glyphs = input.glyph_set();
+        for &glyph in &self.glyphs {
+            glyphs.insert(glyph);
+        }
+        drop(glyphs);
+        let mut drop_tables = input.drop_table_tag_set();
+        for &table in &self.drop_tables {
+            drop_tables.insert(table);
+        }
+        drop(drop_tables);
+        let mut features = input.layout_feature_tag_set();
+        for &feature in &self.keep_features {
+            features.insert(feature);
+        }
+        drop(features);
+        input
+            .name_lang_id_set()
+            .extend(self.name_lang_ids.iter().map(|&id| u32::from(id)));
+        for &(axis, location) in &self.axes {
+            input.pin_axis(axis, location);
+        }
+        input.set_embedding_policy(self.embedding_policy);
+        input.set_validate_output(self.validate_output);
+        input.set_memory_budget(self.memory_budget);
+        Ok(input)
+    }
+}
+
+impl SubsetInput {
+    /// Creates a builder for constructing a subset input in one expression. See [`SubsetInputBuilder`].
+    pub fn builder() -> SubsetInputBuilder {
+        SubsetInputBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_configures_input() {
+        let mut input = SubsetInput::builder()
+            .text("ab")
+            .unicodes(['c', 'a'])
+            .glyphs([GlyphId::new(3)])
+            .drop_tables([Tag::new(b"GPOS")])
+            .keep_features([Tag::new(b"smcp")])
+            .name_languages(["de-CH".parse().unwrap()])
+            .flags(|flags| flags.remove_hinting())
+            .flags(|flags| flags.retain_glyph_names())
+            .pin_axis(Tag::new(b"wght"), AxisLocation::Value(400.0))
+            .pin_axis(Tag::new(b"wght"), AxisLocation::Value(400.0))
+            .embedding_policy(EmbeddingPolicy::Enforce)
+            .validate_output(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            input.unicode_set().iter().collect::<Vec<_>>(),
+            ['a', 'b', 'c']
+        );
+        assert_eq!(
+            input.glyph_set().iter().collect::<Vec<_>>(),
+            [GlyphId::new(3)]
+        );
+        assert!(input.drop_table_tag_set().contains(Tag::new(b"GPOS")));
+        assert!(input.layout_feature_tag_set().contains(Tag::new(b"smcp")));
+        assert!(input.layout_feature_tag_set().contains(Tag::new(b"liga")));
+        assert_eq!(
+            input.name_lang_id_set().iter().collect::<Vec<_>>(),
+            [2, 0x0409, 0x0807]
+        );
+        assert_eq!(
+            *input.flags(),
+            *Flags::default().remove_hinting().retain_glyph_names()
+        );
+        assert_eq!(
+            input.pinned_axes(),
+            [(Tag::new(b"wght"), AxisLocation::Value(400.0))]
+        );
+        assert_eq!(input.embedding_policy(), EmbeddingPolicy::Enforce);
+        assert!(input.validate_output());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_configuration() {
+        let wght = Tag::new(b"wght");
+        assert!(matches!(
+            SubsetInput::builder()
+                .pin_axis(wght, AxisLocation::Value(f32::NAN))
+                .build(),
+            Err(SubsetInputError::InvalidAxisLocation { axis, .. }) if axis == wght
+        ));
+        assert!(matches!(
+            SubsetInput::builder()
+                .pin_axis(wght, AxisLocation::Value(400.0))
+                .pin_axis(wght, AxisLocation::Default)
+                .build(),
+            Err(SubsetInputError::ConflictingAxisLocations(axis)) if axis == wght
+        ));
+        assert!(matches!(
+            SubsetInput::builder()
+                .glyphs([GlyphId::new(u32::MAX)])
+                .build(),
+            Err(SubsetInputError::InvalidGlyph(_))
+        ));
+    }
+}
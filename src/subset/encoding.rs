@@ -0,0 +1,336 @@
+//! Canonical binary encoding of [`SubsetInput`].
+//!
+//! Two inputs which describe the same subset produce the same encoding, no matter in which order they were filled or
+//! whether their sets are inverted internally. This makes the encoding suitable as a part of a cache key. It is also
+//! used for sending subset inputs to worker processes, which decode it back into an equivalent input.
+
+#[cfg(all(feature = "isolated", unix))]
+use crate::Tag;
+use crate::{map::Map, sys, AxisLocation, EmbeddingPolicy, SubsetInput};
+
+/// Identifies the encoding format, so that old on-disk data is never misinterpreted.
+const MAGIC: &[u8; 4] = b"HBSI";
+const VERSION: u8 = 4;
+
+/// All sets of the subset input, in encoding order.
+const SETS: [sys::hb_subset_sets_t; 8] = [
+    sys::hb_subset_sets_t::GLYPH_INDEX,
+    sys::hb_subset_sets_t::UNICODE,
+    sys::hb_subset_sets_t::NO_SUBSET_TABLE_TAG,
+    sys::hb_subset_sets_t::DROP_TABLE_TAG,
+    sys::hb_subset_sets_t::NAME_ID,
+    sys::hb_subset_sets_t::NAME_LANG_ID,
+    sys::hb_subset_sets_t::LAYOUT_FEATURE_TAG,
+    sys::hb_subset_sets_t::LAYOUT_SCRIPT_TAG,
+];
+
+/// Encodes the subset input into its canonical binary form.
+pub(crate) fn encode(input: &SubsetInput) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+
+    let flags = unsafe { sys::hb_subset_input_get_flags(input.as_raw()) };
+    put_u32(&mut out, flags.0);
+    // These do not change the subset, but a cached subset must not bypass the checks
+    out.push(match input.embedding_policy() {
+        EmbeddingPolicy::Ignore => 0,
+        EmbeddingPolicy::Enforce => 1,
+    });
+    out.push(input.validate_output().into());
+
+    for kind in SETS {
+        let set = unsafe { sys::hb_subset_input_set(input.as_raw(), kind) };
+        let ranges = set_ranges(set);
+        put_u32(&mut out, ranges.len() as u32);
+        for (first, last) in ranges {
+            put_u32(&mut out, first);
+            put_u32(&mut out, last);
+        }
+    }
+
+    let mapping = unsafe {
+        Map::<u32, u32>::from_raw(sys::hb_map_reference(
+            sys::hb_subset_input_old_to_new_glyph_mapping(input.as_raw()),
+        ))
+    };
+    let mut mapping = mapping.iter().collect::<Vec<_>>();
+    mapping.sort_unstable();
+    put_u32(&mut out, mapping.len() as u32);
+    for (old, new) in mapping {
+        put_u32(&mut out, old);
+        put_u32(&mut out, new);
+    }
+
+    // Axes are kept sorted by the subset input
+    put_u32(&mut out, input.pinned_axes().len() as u32);
+    for &(tag, location) in input.pinned_axes() {
+        put_u32(&mut out, tag.into());
+        match location {
+            AxisLocation::Default => out.push(0),
+            AxisLocation::Value(value) => {
+                out.push(1);
+                put_u32(&mut out, value.to_bits());
+            }
+        }
+    }
+
+    // Name table overrides are kept sorted by the subset input as well
+    #[cfg(feature = "experimental")]
+    {
+        put_u32(&mut out, input.name_table_overrides().len() as u32);
+        for (record, value) in input.name_table_overrides() {
+            put_u32(&mut out, record.name_id.into());
+            put_u32(&mut out, record.platform_id.into());
+            put_u32(&mut out, record.encoding_id.into());
+            put_u32(&mut out, record.language_id.into());
+            match value {
+                None => out.push(0),
+                Some(value) => {
+                    out.push(1);
+                    put_u32(&mut out, value.len() as u32);
+                    out.extend_from_slice(value.as_bytes());
+                }
+            }
+        }
+    }
+    #[cfg(not(feature = "experimental"))]
+    put_u32(&mut out, 0);
+
+    out
+}
+
+/// Lists the ranges of values in a raw set.
+///
+/// Unlike iterating over values, this is fast even for inverted sets which contain almost all values.
+#[doc(alias = "hb_set_next_range")]
+fn set_ranges(set: *const sys::hb_set_t) -> Vec<(u32, u32)> {
+    let mut ranges = Vec::new();
+    let mut first = sys::HB_SET_VALUE_INVALID;
+    let mut last = sys::HB_SET_VALUE_INVALID;
+    while unsafe { sys::hb_set_next_range(set, &mut first, &mut last) } != 0 {
+        ranges.push((first, last));
+    }
+    ranges
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Decodes a subset input from the form produced by [`encode`].
+///
+/// Returns `None` if the data is malformed, or it was encoded by another version of this crate.
+#[cfg(all(feature = "isolated", unix))]
+pub(crate) fn decode(data: &[u8]) -> Option<SubsetInput> {
+    let mut reader = Reader(data);
+    if reader.bytes(MAGIC.len())? != MAGIC || reader.u8()? != VERSION {
+        return None;
+    }
+
+    let mut input = SubsetInput::new().ok()?;
+    let flags = reader.u32()?;
+    unsafe { sys::hb_subset_input_set_flags(input.as_raw(), flags) };
+    input.set_embedding_policy(match reader.u8()? {
+        0 => EmbeddingPolicy::Ignore,
+        1 => EmbeddingPolicy::Enforce,
+        _ => return None,
+    });
+    input.set_validate_output(reader.u8()? != 0);
+
+    for kind in SETS {
+        let set = unsafe { sys::hb_subset_input_set(input.as_raw(), kind) };
+        let ranges = (0..reader.u32()?)
+            .map(|_| Some((reader.u32()?, reader.u32()?)))
+            .collect::<Option<Vec<_>>>()?;
+        unsafe { sys::hb_set_clear(set) };
+        if ranges
+            .last()
+            .is_some_and(|&(_, last)| last == sys::HB_SET_VALUE_INVALID - 1)
+        {
+            // The set was inverted. Adding the ranges would allocate pages for billions of values, so invert the set
+            // again and remove the gaps between the ranges instead.
+            unsafe { sys::hb_set_invert(set) };
+            let mut next = 0;
+            for (first, last) in ranges {
+                if first > next {
+                    unsafe { sys::hb_set_del_range(set, next, first - 1) };
+                }
+                next = last.saturating_add(1);
+            }
+        } else {
+            for (first, last) in ranges {
+                unsafe { sys::hb_set_add_range(set, first, last) };
+            }
+        }
+    }
+
+    let mapping = unsafe { sys::hb_subset_input_old_to_new_glyph_mapping(input.as_raw()) };
+    for _ in 0..reader.u32()? {
+        let (old, new) = (reader.u32()?, reader.u32()?);
+        unsafe { sys::hb_map_set(mapping, old, new) };
+    }
+
+    for _ in 0..reader.u32()? {
+        let tag = Tag::from(reader.u32()?);
+        let location = match reader.u8()? {
+            0 => AxisLocation::Default,
+            1 => AxisLocation::Value(f32::from_bits(reader.u32()?)),
+            _ => return None,
+        };
+        input.pin_axis(tag, location);
+    }
+
+    let names = reader.u32()?;
+    #[cfg(feature = "experimental")]
+    for _ in 0..names {
+        let name_id = u16::try_from(reader.u32()?).ok()?;
+        let platform_id = u16::try_from(reader.u32()?).ok()?;
+        let encoding_id = u16::try_from(reader.u32()?).ok()?;
+        let language_id = u16::try_from(reader.u32()?).ok()?;
+        let value = match reader.u8()? {
+            0 => None,
+            1 => {
+                let len = reader.u32()? as usize;
+                Some(std::str::from_utf8(reader.bytes(len)?).ok()?)
+            }
+            _ => return None,
+        };
+        input
+            .override_name_table(name_id, platform_id, encoding_id, language_id, value)
+            .ok()?;
+    }
+    #[cfg(not(feature = "experimental"))]
+    if names != 0 {
+        return None;
+    }
+
+    reader.0.is_empty().then_some(input)
+}
+
+/// Reads values from encoded data.
+#[cfg(all(feature = "isolated", unix))]
+struct Reader<'a>(&'a [u8]);
+
+#[cfg(all(feature = "isolated", unix))]
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NewGlyphId, OldGlyphId, Tag};
+
+    #[test]
+    fn encoding_does_not_depend_on_insertion_order() {
+        let mut a = SubsetInput::new().unwrap();
+        a.unicode_set().insert('a');
+        a.unicode_set().insert('b');
+        a.old_to_new_glyph_mapping()
+            .insert(OldGlyphId::new(1), NewGlyphId::new(2));
+        a.old_to_new_glyph_mapping()
+            .insert(OldGlyphId::new(3), NewGlyphId::new(4));
+        a.pin_axis_to_default(Tag::new(b"wght"));
+        a.pin_axis_location(Tag::new(b"wdth"), 80.0);
+
+        let mut b = SubsetInput::new().unwrap();
+        b.pin_axis_location(Tag::new(b"wdth"), 80.0);
+        b.pin_axis_to_default(Tag::new(b"wght"));
+        b.old_to_new_glyph_mapping()
+            .insert(OldGlyphId::new(3), NewGlyphId::new(4));
+        b.old_to_new_glyph_mapping()
+            .insert(OldGlyphId::new(1), NewGlyphId::new(2));
+        b.unicode_set().insert('b');
+        b.unicode_set().insert('a');
+
+        assert_eq!(encode(&a), encode(&b));
+    }
+
+    #[test]
+    fn encoding_detects_differences() {
+        let base = encode(&SubsetInput::new().unwrap());
+
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+        assert_ne!(encode(&input), base);
+
+        let mut input = SubsetInput::new().unwrap();
+        input.flags().retain_glyph_names();
+        assert_ne!(encode(&input), base);
+
+        let mut input = SubsetInput::new().unwrap();
+        input.drop_table_tag_set().insert(Tag::new(b"GSUB"));
+        assert_ne!(encode(&input), base);
+
+        let mut input = SubsetInput::new().unwrap();
+        input.pin_axis_to_default(Tag::new(b"wght"));
+        assert_ne!(encode(&input), base);
+
+        let mut input = SubsetInput::new().unwrap();
+        input.set_embedding_policy(EmbeddingPolicy::Enforce);
+        assert_ne!(encode(&input), base);
+    }
+
+    #[cfg(all(feature = "isolated", unix))]
+    #[test]
+    fn decoding_restores_input() {
+        let mut input = SubsetInput::new().unwrap();
+        input.unicode_set().insert('a');
+        input.unicode_set().insert('c');
+        input.flags().retain_glyph_names();
+        input.drop_table_tag_set().insert(Tag::new(b"GSUB"));
+        input
+            .old_to_new_glyph_mapping()
+            .insert(OldGlyphId::new(1), NewGlyphId::new(2));
+        input.pin_axis_location(Tag::new(b"wght"), 700.0);
+        input.set_embedding_policy(EmbeddingPolicy::Enforce);
+        input.set_validate_output(true);
+        let encoded = encode(&input);
+        assert_eq!(encode(&decode(&encoded).unwrap()), encoded);
+
+        let mut input = SubsetInput::new().unwrap();
+        input.keep_everything();
+        input.unicode_set().remove('b');
+        let encoded = encode(&input);
+        let mut decoded = decode(&encoded).unwrap();
+        assert_eq!(encode(&decoded), encoded);
+        assert!(!decoded.unicode_set().contains('b'));
+        assert!(decoded.unicode_set().contains('c'));
+
+        assert!(decode(&encoded[..encoded.len() - 1]).is_none());
+        assert!(decode(b"HBSI").is_none());
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn encoding_detects_name_overrides() {
+        let base = encode(&SubsetInput::new().unwrap());
+
+        let mut set = SubsetInput::new().unwrap();
+        set.override_name_table(crate::NameId::FONT_FAMILY, 3, 1, 0x409, Some("A"))
+            .unwrap();
+        let mut delete = SubsetInput::new().unwrap();
+        delete
+            .override_name_table(crate::NameId::FONT_FAMILY, 3, 1, 0x409, None)
+            .unwrap();
+        assert_ne!(encode(&set), base);
+        assert_ne!(encode(&delete), base);
+        assert_ne!(encode(&set), encode(&delete));
+    }
+}
@@ -28,7 +28,18 @@ use std::ops::DerefMut;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Flags(pub sys::hb_subset_flags_t);
 
+/// `HB_SUBSET_FLAGS_NO_LAYOUT_CLOSURE`, which is not declared by HarfBuzz older than 7.2.
+#[cfg(hb_subset_no_layout_closure)]
+const NO_LAYOUT_CLOSURE: sys::hb_subset_flags_t = sys::hb_subset_flags_t::NO_LAYOUT_CLOSURE;
+#[cfg(not(hb_subset_no_layout_closure))]
+const NO_LAYOUT_CLOSURE: sys::hb_subset_flags_t = sys::hb_subset_flags_t(0x200);
+
 impl Flags {
+    /// Checks whether the layout closure is skipped. See [`Self::no_layout_closure`].
+    pub(crate) fn skips_layout_closure(&self) -> bool {
+        self.0 .0 & NO_LAYOUT_CLOSURE.0 != 0
+    }
+
     fn add_flag(&mut self, flag: sys::hb_subset_flags_t) -> &mut Self {
         self.0 |= flag;
         self
@@ -142,12 +153,14 @@ impl Flags {
     /// For example, if glyphs corresponding to `f` and `i` are retained, then also glyphs corresponding to `ﬀ`, `ﬁ` and
     /// `ﬃ` are retained.
     pub fn retain_layout_closure(&mut self) -> &mut Self {
-        self.remove_flag(sys::hb_subset_flags_t::NO_LAYOUT_CLOSURE)
+        self.remove_flag(NO_LAYOUT_CLOSURE)
     }
 
     /// Instructs subsetter to keep only minimum set of glyphs disregarding layout closure.
+    ///
+    /// Requires HarfBuzz 7.2 or newer, see [`Capability::NoLayoutClosure`](crate::Capability::NoLayoutClosure).
     pub fn no_layout_closure(&mut self) -> &mut Self {
-        self.add_flag(sys::hb_subset_flags_t::NO_LAYOUT_CLOSURE)
+        self.add_flag(NO_LAYOUT_CLOSURE)
     }
 }
 
@@ -0,0 +1,228 @@
+use crate::{
+    tags::{feature, table},
+    AllocationError, Capability, NameId, SubsetInput, SubsetInputError, Tag,
+};
+
+/// Tables which only matter for legacy rasterizers or for signing the original font, and which are therefore useless
+/// or even harmful in a subset:
+/// - `DSIG`: the signature is invalidated by subsetting.
+/// - `hdmx`, `VDMX`, `LTSH`: precomputed device metrics for hinted rendering on old Windows rasterizers.
+const LEGACY_TABLES: [Tag; 4] = [table::DSIG, table::HDMX, table::VDMX, table::LTSH];
+
+/// Layout tables, which are not needed once text has been shaped, e.g. when it is embedded into a PDF as glyph ids.
+const LAYOUT_TABLES: [Tag; 6] = [
+    table::GSUB,
+    table::GPOS,
+    table::GDEF,
+    table::BASE,
+    table::JSTF,
+    table::MATH,
+];
+
+/// Layout features browsers apply by default, or which are commonly enabled from CSS with `font-feature-settings`.
+///
+/// Most of these are in the default feature set of HarfBuzz already, but they are listed explicitly so that the preset
+/// does not depend on the HarfBuzz version.
+const WEB_FONT_FEATURES: [Tag; 17] = [
+    // Required and contextual forms
+    feature::CCMP,
+    feature::LOCL,
+    feature::RLIG,
+    feature::RCLT,
+    feature::CALT,
+    feature::CURS,
+    // Ligatures
+    feature::LIGA,
+    feature::CLIG,
+    // Positioning
+    feature::KERN,
+    feature::MARK,
+    feature::MKMK,
+    feature::DIST,
+    // Vertical text
+    feature::VERT,
+    feature::VKRN,
+    // Fractions, which shapers apply automatically around the fraction slash
+    feature::FRAC,
+    feature::NUMR,
+    feature::DNOM,
+];
+
+/// Name ids browsers use for matching and displaying fonts:
+/// - 0: copyright notice, which the license of the font usually requires to be retained.
+/// - 1, 2, 4, 16, 17: family, subfamily and full names, including the typographic ones.
+/// - 3: unique identifier.
+/// - 5: version string.
+/// - 6: PostScript name, which is also used for matching `local()` sources.
+const WEB_FONT_NAME_IDS: [NameId; 9] = [
+    NameId::COPYRIGHT,
+    NameId::FONT_FAMILY,
+    NameId::FONT_SUBFAMILY,
+    NameId::UNIQUE_ID,
+    NameId::FULL_NAME,
+    NameId::VERSION_STRING,
+    NameId::POSTSCRIPT_NAME,
+    NameId::TYPOGRAPHIC_FAMILY,
+    NameId::TYPOGRAPHIC_SUBFAMILY,
+];
+
+/// Presets for common use cases.
+impl SubsetInput {
+    /// Creates a subset input tuned for serving fonts on the web.
+    ///
+    /// The preset
+    /// - removes hinting, as browsers on modern platforms render fonts unhinted, and hinting is a large part of the
+    ///   font,
+    /// - removes glyph names. Legacy name records, e.g. Mac names, are not retained either, which is also the default
+    ///   of [`Self::new`],
+    /// - drops `DSIG`, `hdmx`, `VDMX` and `LTSH` tables,
+    /// - keeps the default layout features, and additionally the features browsers enable by default, such as `kern`
+    ///   and `liga`,
+    /// - keeps only the name ids browsers need for matching and displaying the font.
+    ///
+    /// The characters to retain still need to be added to [`Self::unicode_set`].
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::web_font()?;
+    /// subset.unicode_set().insert_range('a'..='z');
+    /// let new_font = subset.subset_font(&font)?;
+    /// assert!(new_font.table(Tag::new(b"fpgm")).is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn web_font() -> Result<Self, AllocationError> {
+        let mut input = Self::new()?;
+        input
+            .flags()
+            .remove_hinting()
+            .remove_legacy_names()
+            .remove_glyph_names();
+        input.insert_tables_to_drop(LEGACY_TABLES);
+        let mut features = input.layout_feature_tag_set();
+        for feature in WEB_FONT_FEATURES {
+            features.insert(feature);
+        }
+        drop(features);
+        let mut name_ids = input.name_id_set();
+        name_ids.clear();
+        name_ids.extend(WEB_FONT_NAME_IDS);
+        drop(name_ids);
+        Ok(input)
+    }
+
+    /// Creates a subset input tuned for embedding fonts into PDF documents.
+    ///
+    /// Text in PDF documents is already shaped and refers to glyphs by their ids, so the preset
+    /// - retains glyph ids, so that glyph ids in content streams stay valid,
+    /// - drops layout tables (`GSUB`, `GPOS`, `GDEF`, `BASE`, `JSTF` and `MATH`) and does not compute the layout
+    ///   closure,
+    /// - retains the outline of `.notdef`, which viewers show for missing glyphs,
+    /// - drops `DSIG`, `hdmx`, `VDMX` and `LTSH` tables.
+    ///
+    /// Hinting is retained, as PDF documents are often printed or rasterized at low resolution. The glyphs to retain
+    /// need to be added to [`Self::glyph_set`], and the characters to [`Self::unicode_set`] if the subset should
+    /// support text extraction through its `cmap`.
+    ///
+    /// Skipping the layout closure requires HarfBuzz 7.2, so this fails with [`SubsetInputError::Unsupported`] on older
+    /// versions. See [`Capability::NoLayoutClosure`].
+    pub fn pdf_embedding() -> Result<Self, SubsetInputError> {
+        Capability::NoLayoutClosure.check()?;
+        let mut input = Self::new()?;
+        input
+            .flags()
+            .retain_glyph_indices()
+            .retain_notdef_outline()
+            .no_layout_closure();
+        input.insert_tables_to_drop(LEGACY_TABLES);
+        input.insert_tables_to_drop(LAYOUT_TABLES);
+        input.layout_feature_tag_set().clear();
+        Ok(input)
+    }
+
+    /// Creates a subset input which keeps all layout features and scripts of the font.
+    ///
+    /// Unlike with [`Self::keep_everything`], only the given characters and glyphs are retained, but they can be used
+    /// with any feature the font supports for them, e.g. small caps or stylistic alternates. Features which do not
+    /// apply to any retained glyph are still pruned. The layout closure is computed over all features, so this can
+    /// retain considerably more glyphs than the default features do.
+    pub fn keep_layout_everything() -> Result<Self, AllocationError> {
+        let mut input = Self::new()?;
+        input.flags().retain_layout_closure();
+        input.layout_feature_tag_set().insert_range(..);
+        input.layout_script_tag_set().insert_range(..);
+        Ok(input)
+    }
+
+    fn insert_tables_to_drop(&mut self, tables: impl IntoIterator<Item = Tag>) {
+        self.drop_table_tag_set().extend(tables);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob, FontFace};
+
+    #[test]
+    fn web_font_drops_hinting_and_keeps_features() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::web_font().unwrap();
+        subset.unicode_set().insert_range('a'..='z');
+        assert!(subset.name_id_set().contains(NameId::TYPOGRAPHIC_FAMILY));
+        assert!(!subset.name_id_set().contains(NameId::TRADEMARK));
+
+        let plan = subset.plan(&font).unwrap();
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(features.contains(Tag::new(b"liga")));
+        assert!(!features.contains(Tag::new(b"smcp")));
+        drop(plan);
+
+        let new_font = subset.subset_font(&font).unwrap();
+        for table in [b"fpgm", b"prep", b"cvt ", b"hdmx", b"DSIG"] {
+            assert!(new_font.table(Tag::new(table)).is_empty());
+        }
+        assert!(!new_font.table(Tag::new(b"GPOS")).is_empty());
+    }
+
+    #[test]
+    fn pdf_embedding_retains_glyph_ids() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let glyph = font.nominal_glyph_mapping().unwrap().get('z').unwrap();
+        if !Capability::NoLayoutClosure.is_supported() {
+            assert!(matches!(
+                SubsetInput::pdf_embedding(),
+                Err(SubsetInputError::Unsupported(_))
+            ));
+            return;
+        }
+        let mut subset = SubsetInput::pdf_embedding().unwrap();
+        subset.glyph_set().insert(glyph);
+
+        let new_font = subset.subset_font(&font).unwrap();
+        assert_eq!(new_font.glyph_count(), glyph.to_u32() as usize + 1);
+        assert!(!new_font.glyph_data(glyph).unwrap().is_empty());
+        for table in [b"GSUB", b"GPOS", b"GDEF"] {
+            assert!(new_font.table(Tag::new(table)).is_empty());
+        }
+    }
+
+    #[test]
+    fn keep_layout_everything_keeps_all_features() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::keep_layout_everything().unwrap();
+        subset.unicode_set().insert('a');
+        let plan = subset.plan(&font).unwrap();
+        let features = plan.layout_feature_tag_set().unwrap();
+        assert!(features.contains(Tag::new(b"smcp")));
+        let font_features =
+            [Tag::new(b"GSUB"), Tag::new(b"GPOS")].map(|table| font.layout_feature_tags(table));
+        assert!(features
+            .iter()
+            .all(|feature| font_features.iter().any(|tags| tags.contains(&feature))));
+        assert!(plan.glyphset().unwrap().len() > 2);
+    }
+}
@@ -29,4 +29,22 @@ mod tests {
             "The minimum supported version of HarfBuzz is 7.0.0"
         );
     }
+
+    #[test]
+    // Comparing needs bindgen to generate bindings for the bundled headers, and WASI tests cannot read the files
+    #[cfg(all(feature = "bindgen", not(target_family = "wasm")))]
+    fn pregenerated_bindings_match_bundled_headers() {
+        let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/bundled_bindings.rs"))
+            .expect(
+            "Bindings for the bundled headers were not generated, check out the HarfBuzz submodule",
+        );
+        let pregenerated =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/bindings/harfbuzz.rs"))
+                .unwrap_or_default();
+        assert!(
+            generated == pregenerated,
+            "Pre-generated bindings are missing or out of date, regenerate them with \
+            `HB_SUBSET_UPDATE_BINDINGS=1 cargo build --features bindgen`"
+        );
+    }
 }
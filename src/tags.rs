@@ -0,0 +1,656 @@
+//! Constants for well-known tags.
+//!
+//! The constants can be used wherever a [`Tag`] is expected, e.g. for filling
+//! [`SubsetInput::drop_table_tag_set`](crate::SubsetInput::drop_table_tag_set):
+//! ```
+//! # use hb_subset::{*, tags::table};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut subset = SubsetInput::new()?;
+//! subset.drop_table_tag_set().insert(table::GPOS);
+//! assert_eq!(table::CFF.to_string(), "CFF ");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Tag;
+
+/// Tags of tables defined in the [OpenType specification](https://learn.microsoft.com/en-us/typography/opentype/spec/),
+/// and some Apple Advanced Typography tables.
+///
+/// Tags with spaces or other characters which cannot be used in identifiers are named without them, e.g.
+/// [`CFF`](table::CFF) for `CFF ` and [`OS2`](table::OS2) for `OS/2`.
+pub mod table {
+    use super::Tag;
+
+    /// `avar`: Axis variations.
+    pub const AVAR: Tag = Tag::from_bytes(b"avar");
+
+    /// `BASE`: Baseline data.
+    pub const BASE: Tag = Tag::from_bytes(b"BASE");
+
+    /// `CBDT`: Color bitmap data.
+    pub const CBDT: Tag = Tag::from_bytes(b"CBDT");
+
+    /// `CBLC`: Color bitmap location data.
+    pub const CBLC: Tag = Tag::from_bytes(b"CBLC");
+
+    /// `CFF `: Compact Font Format outlines.
+    pub const CFF: Tag = Tag::from_bytes(b"CFF ");
+
+    /// `CFF2`: Compact Font Format 2 outlines.
+    pub const CFF2: Tag = Tag::from_bytes(b"CFF2");
+
+    /// `cmap`: Character to glyph index mapping.
+    pub const CMAP: Tag = Tag::from_bytes(b"cmap");
+
+    /// `COLR`: Color glyph layers.
+    pub const COLR: Tag = Tag::from_bytes(b"COLR");
+
+    /// `CPAL`: Color palettes.
+    pub const CPAL: Tag = Tag::from_bytes(b"CPAL");
+
+    /// `cvar`: Control value table variations.
+    pub const CVAR: Tag = Tag::from_bytes(b"cvar");
+
+    /// `cvt `: Control value table.
+    pub const CVT: Tag = Tag::from_bytes(b"cvt ");
+
+    /// `DSIG`: Digital signature.
+    pub const DSIG: Tag = Tag::from_bytes(b"DSIG");
+
+    /// `EBDT`: Embedded bitmap data.
+    pub const EBDT: Tag = Tag::from_bytes(b"EBDT");
+
+    /// `EBLC`: Embedded bitmap location data.
+    pub const EBLC: Tag = Tag::from_bytes(b"EBLC");
+
+    /// `EBSC`: Embedded bitmap scaling data.
+    pub const EBSC: Tag = Tag::from_bytes(b"EBSC");
+
+    /// `fpgm`: Font program.
+    pub const FPGM: Tag = Tag::from_bytes(b"fpgm");
+
+    /// `fvar`: Font variations.
+    pub const FVAR: Tag = Tag::from_bytes(b"fvar");
+
+    /// `gasp`: Grid-fitting and scan-conversion procedure.
+    pub const GASP: Tag = Tag::from_bytes(b"gasp");
+
+    /// `GDEF`: Glyph definition data.
+    pub const GDEF: Tag = Tag::from_bytes(b"GDEF");
+
+    /// `glyf`: TrueType glyph outlines.
+    pub const GLYF: Tag = Tag::from_bytes(b"glyf");
+
+    /// `GPOS`: Glyph positioning data.
+    pub const GPOS: Tag = Tag::from_bytes(b"GPOS");
+
+    /// `GSUB`: Glyph substitution data.
+    pub const GSUB: Tag = Tag::from_bytes(b"GSUB");
+
+    /// `gvar`: Glyph variations.
+    pub const GVAR: Tag = Tag::from_bytes(b"gvar");
+
+    /// `hdmx`: Horizontal device metrics.
+    pub const HDMX: Tag = Tag::from_bytes(b"hdmx");
+
+    /// `head`: Font header.
+    pub const HEAD: Tag = Tag::from_bytes(b"head");
+
+    /// `hhea`: Horizontal header.
+    pub const HHEA: Tag = Tag::from_bytes(b"hhea");
+
+    /// `hmtx`: Horizontal metrics.
+    pub const HMTX: Tag = Tag::from_bytes(b"hmtx");
+
+    /// `HVAR`: Horizontal metrics variations.
+    pub const HVAR: Tag = Tag::from_bytes(b"HVAR");
+
+    /// `JSTF`: Justification data.
+    pub const JSTF: Tag = Tag::from_bytes(b"JSTF");
+
+    /// `kern`: Legacy kerning.
+    pub const KERN: Tag = Tag::from_bytes(b"kern");
+
+    /// `loca`: Index to location of TrueType glyph outlines.
+    pub const LOCA: Tag = Tag::from_bytes(b"loca");
+
+    /// `LTSH`: Linear threshold data.
+    pub const LTSH: Tag = Tag::from_bytes(b"LTSH");
+
+    /// `MATH`: Mathematical typesetting data.
+    pub const MATH: Tag = Tag::from_bytes(b"MATH");
+
+    /// `maxp`: Maximum profile.
+    pub const MAXP: Tag = Tag::from_bytes(b"maxp");
+
+    /// `MERG`: Merge data.
+    pub const MERG: Tag = Tag::from_bytes(b"MERG");
+
+    /// `meta`: Metadata.
+    pub const META: Tag = Tag::from_bytes(b"meta");
+
+    /// `MVAR`: Metrics variations.
+    pub const MVAR: Tag = Tag::from_bytes(b"MVAR");
+
+    /// `name`: Naming table.
+    pub const NAME: Tag = Tag::from_bytes(b"name");
+
+    /// `OS/2`: OS/2 and Windows specific metrics.
+    pub const OS2: Tag = Tag::from_bytes(b"OS/2");
+
+    /// `PCLT`: PCL 5 data.
+    pub const PCLT: Tag = Tag::from_bytes(b"PCLT");
+
+    /// `post`: PostScript information.
+    pub const POST: Tag = Tag::from_bytes(b"post");
+
+    /// `prep`: Control value program.
+    pub const PREP: Tag = Tag::from_bytes(b"prep");
+
+    /// `sbix`: Standard bitmap graphics.
+    pub const SBIX: Tag = Tag::from_bytes(b"sbix");
+
+    /// `STAT`: Style attributes.
+    pub const STAT: Tag = Tag::from_bytes(b"STAT");
+
+    /// `SVG `: SVG glyph descriptions.
+    pub const SVG: Tag = Tag::from_bytes(b"SVG ");
+
+    /// `VDMX`: Vertical device metrics.
+    pub const VDMX: Tag = Tag::from_bytes(b"VDMX");
+
+    /// `vhea`: Vertical header.
+    pub const VHEA: Tag = Tag::from_bytes(b"vhea");
+
+    /// `vmtx`: Vertical metrics.
+    pub const VMTX: Tag = Tag::from_bytes(b"vmtx");
+
+    /// `VORG`: Vertical origin.
+    pub const VORG: Tag = Tag::from_bytes(b"VORG");
+
+    /// `VVAR`: Vertical metrics variations.
+    pub const VVAR: Tag = Tag::from_bytes(b"VVAR");
+
+    /// `morx`: AAT extended glyph metamorphosis.
+    pub const MORX: Tag = Tag::from_bytes(b"morx");
+
+    /// `kerx`: AAT extended kerning.
+    pub const KERX: Tag = Tag::from_bytes(b"kerx");
+
+    /// `trak`: AAT tracking.
+    pub const TRAK: Tag = Tag::from_bytes(b"trak");
+}
+
+/// Tags of [registered layout features](https://learn.microsoft.com/en-us/typography/opentype/spec/featuretags).
+///
+/// Character variants `cv01` to `cv99` are not listed individually, but can be constructed with
+/// [`character_variant`](feature::character_variant).
+pub mod feature {
+    use super::Tag;
+
+    /// Constructs the tag of character variant feature `cv01` to `cv99`.
+    ///
+    /// # Panics
+    /// Panics if `n` is not between 1 and 99.
+    pub const fn character_variant(n: u8) -> Tag {
+        assert!(
+            matches!(n, 1..=99),
+            "character variants are numbered from 1 to 99"
+        );
+        Tag::from_bytes(&[b'c', b'v', b'0' + n / 10, b'0' + n % 10])
+    }
+
+    /// `aalt`: Access all alternates.
+    pub const AALT: Tag = Tag::from_bytes(b"aalt");
+
+    /// `abvf`: Above-base forms.
+    pub const ABVF: Tag = Tag::from_bytes(b"abvf");
+
+    /// `abvm`: Above-base mark positioning.
+    pub const ABVM: Tag = Tag::from_bytes(b"abvm");
+
+    /// `abvs`: Above-base substitutions.
+    pub const ABVS: Tag = Tag::from_bytes(b"abvs");
+
+    /// `afrc`: Alternative fractions.
+    pub const AFRC: Tag = Tag::from_bytes(b"afrc");
+
+    /// `akhn`: Akhand.
+    pub const AKHN: Tag = Tag::from_bytes(b"akhn");
+
+    /// `apkn`: Kerning for alternate proportional widths.
+    pub const APKN: Tag = Tag::from_bytes(b"apkn");
+
+    /// `blwf`: Below-base forms.
+    pub const BLWF: Tag = Tag::from_bytes(b"blwf");
+
+    /// `blwm`: Below-base mark positioning.
+    pub const BLWM: Tag = Tag::from_bytes(b"blwm");
+
+    /// `blws`: Below-base substitutions.
+    pub const BLWS: Tag = Tag::from_bytes(b"blws");
+
+    /// `c2pc`: Petite capitals from capitals.
+    pub const C2PC: Tag = Tag::from_bytes(b"c2pc");
+
+    /// `c2sc`: Small capitals from capitals.
+    pub const C2SC: Tag = Tag::from_bytes(b"c2sc");
+
+    /// `calt`: Contextual alternates.
+    pub const CALT: Tag = Tag::from_bytes(b"calt");
+
+    /// `case`: Case-sensitive forms.
+    pub const CASE: Tag = Tag::from_bytes(b"case");
+
+    /// `ccmp`: Glyph composition and decomposition.
+    pub const CCMP: Tag = Tag::from_bytes(b"ccmp");
+
+    /// `cfar`: Conjunct form after ro.
+    pub const CFAR: Tag = Tag::from_bytes(b"cfar");
+
+    /// `chws`: Contextual half-width spacing.
+    pub const CHWS: Tag = Tag::from_bytes(b"chws");
+
+    /// `cjct`: Conjunct forms.
+    pub const CJCT: Tag = Tag::from_bytes(b"cjct");
+
+    /// `clig`: Contextual ligatures.
+    pub const CLIG: Tag = Tag::from_bytes(b"clig");
+
+    /// `cpct`: Centered CJK punctuation.
+    pub const CPCT: Tag = Tag::from_bytes(b"cpct");
+
+    /// `cpsp`: Capital spacing.
+    pub const CPSP: Tag = Tag::from_bytes(b"cpsp");
+
+    /// `cswh`: Contextual swash.
+    pub const CSWH: Tag = Tag::from_bytes(b"cswh");
+
+    /// `curs`: Cursive positioning.
+    pub const CURS: Tag = Tag::from_bytes(b"curs");
+
+    /// `dist`: Distances.
+    pub const DIST: Tag = Tag::from_bytes(b"dist");
+
+    /// `dlig`: Discretionary ligatures.
+    pub const DLIG: Tag = Tag::from_bytes(b"dlig");
+
+    /// `dnom`: Denominators.
+    pub const DNOM: Tag = Tag::from_bytes(b"dnom");
+
+    /// `dtls`: Dotless forms.
+    pub const DTLS: Tag = Tag::from_bytes(b"dtls");
+
+    /// `expt`: Expert forms.
+    pub const EXPT: Tag = Tag::from_bytes(b"expt");
+
+    /// `falt`: Final glyph on line alternates.
+    pub const FALT: Tag = Tag::from_bytes(b"falt");
+
+    /// `fin2`: Terminal forms #2.
+    pub const FIN2: Tag = Tag::from_bytes(b"fin2");
+
+    /// `fin3`: Terminal forms #3.
+    pub const FIN3: Tag = Tag::from_bytes(b"fin3");
+
+    /// `fina`: Terminal forms.
+    pub const FINA: Tag = Tag::from_bytes(b"fina");
+
+    /// `flac`: Flattened accent forms.
+    pub const FLAC: Tag = Tag::from_bytes(b"flac");
+
+    /// `frac`: Fractions.
+    pub const FRAC: Tag = Tag::from_bytes(b"frac");
+
+    /// `fwid`: Full widths.
+    pub const FWID: Tag = Tag::from_bytes(b"fwid");
+
+    /// `half`: Half forms.
+    pub const HALF: Tag = Tag::from_bytes(b"half");
+
+    /// `haln`: Halant forms.
+    pub const HALN: Tag = Tag::from_bytes(b"haln");
+
+    /// `halt`: Alternate half widths.
+    pub const HALT: Tag = Tag::from_bytes(b"halt");
+
+    /// `hist`: Historical forms.
+    pub const HIST: Tag = Tag::from_bytes(b"hist");
+
+    /// `hkna`: Horizontal kana alternates.
+    pub const HKNA: Tag = Tag::from_bytes(b"hkna");
+
+    /// `hlig`: Historical ligatures.
+    pub const HLIG: Tag = Tag::from_bytes(b"hlig");
+
+    /// `hngl`: Hangul.
+    pub const HNGL: Tag = Tag::from_bytes(b"hngl");
+
+    /// `hojo`: Hojo kanji forms.
+    pub const HOJO: Tag = Tag::from_bytes(b"hojo");
+
+    /// `hwid`: Half widths.
+    pub const HWID: Tag = Tag::from_bytes(b"hwid");
+
+    /// `init`: Initial forms.
+    pub const INIT: Tag = Tag::from_bytes(b"init");
+
+    /// `isol`: Isolated forms.
+    pub const ISOL: Tag = Tag::from_bytes(b"isol");
+
+    /// `ital`: Italics.
+    pub const ITAL: Tag = Tag::from_bytes(b"ital");
+
+    /// `jalt`: Justification alternates.
+    pub const JALT: Tag = Tag::from_bytes(b"jalt");
+
+    /// `jp04`: JIS2004 forms.
+    pub const JP04: Tag = Tag::from_bytes(b"jp04");
+
+    /// `jp78`: JIS78 forms.
+    pub const JP78: Tag = Tag::from_bytes(b"jp78");
+
+    /// `jp83`: JIS83 forms.
+    pub const JP83: Tag = Tag::from_bytes(b"jp83");
+
+    /// `jp90`: JIS90 forms.
+    pub const JP90: Tag = Tag::from_bytes(b"jp90");
+
+    /// `kern`: Kerning.
+    pub const KERN: Tag = Tag::from_bytes(b"kern");
+
+    /// `lfbd`: Left bounds.
+    pub const LFBD: Tag = Tag::from_bytes(b"lfbd");
+
+    /// `liga`: Standard ligatures.
+    pub const LIGA: Tag = Tag::from_bytes(b"liga");
+
+    /// `ljmo`: Leading jamo forms.
+    pub const LJMO: Tag = Tag::from_bytes(b"ljmo");
+
+    /// `lnum`: Lining figures.
+    pub const LNUM: Tag = Tag::from_bytes(b"lnum");
+
+    /// `locl`: Localized forms.
+    pub const LOCL: Tag = Tag::from_bytes(b"locl");
+
+    /// `ltra`: Left-to-right alternates.
+    pub const LTRA: Tag = Tag::from_bytes(b"ltra");
+
+    /// `ltrm`: Left-to-right mirrored forms.
+    pub const LTRM: Tag = Tag::from_bytes(b"ltrm");
+
+    /// `mark`: Mark positioning.
+    pub const MARK: Tag = Tag::from_bytes(b"mark");
+
+    /// `med2`: Medial forms #2.
+    pub const MED2: Tag = Tag::from_bytes(b"med2");
+
+    /// `medi`: Medial forms.
+    pub const MEDI: Tag = Tag::from_bytes(b"medi");
+
+    /// `mgrk`: Mathematical Greek.
+    pub const MGRK: Tag = Tag::from_bytes(b"mgrk");
+
+    /// `mkmk`: Mark to mark positioning.
+    pub const MKMK: Tag = Tag::from_bytes(b"mkmk");
+
+    /// `mset`: Mark positioning via substitution.
+    pub const MSET: Tag = Tag::from_bytes(b"mset");
+
+    /// `nalt`: Alternate annotation forms.
+    pub const NALT: Tag = Tag::from_bytes(b"nalt");
+
+    /// `nlck`: NLC kanji forms.
+    pub const NLCK: Tag = Tag::from_bytes(b"nlck");
+
+    /// `nukt`: Nukta forms.
+    pub const NUKT: Tag = Tag::from_bytes(b"nukt");
+
+    /// `numr`: Numerators.
+    pub const NUMR: Tag = Tag::from_bytes(b"numr");
+
+    /// `onum`: Oldstyle figures.
+    pub const ONUM: Tag = Tag::from_bytes(b"onum");
+
+    /// `opbd`: Optical bounds.
+    pub const OPBD: Tag = Tag::from_bytes(b"opbd");
+
+    /// `ordn`: Ordinals.
+    pub const ORDN: Tag = Tag::from_bytes(b"ordn");
+
+    /// `ornm`: Ornaments.
+    pub const ORNM: Tag = Tag::from_bytes(b"ornm");
+
+    /// `palt`: Proportional alternate widths.
+    pub const PALT: Tag = Tag::from_bytes(b"palt");
+
+    /// `pcap`: Petite capitals.
+    pub const PCAP: Tag = Tag::from_bytes(b"pcap");
+
+    /// `pkna`: Proportional kana.
+    pub const PKNA: Tag = Tag::from_bytes(b"pkna");
+
+    /// `pnum`: Proportional figures.
+    pub const PNUM: Tag = Tag::from_bytes(b"pnum");
+
+    /// `pref`: Pre-base forms.
+    pub const PREF: Tag = Tag::from_bytes(b"pref");
+
+    /// `pres`: Pre-base substitutions.
+    pub const PRES: Tag = Tag::from_bytes(b"pres");
+
+    /// `pstf`: Post-base forms.
+    pub const PSTF: Tag = Tag::from_bytes(b"pstf");
+
+    /// `psts`: Post-base substitutions.
+    pub const PSTS: Tag = Tag::from_bytes(b"psts");
+
+    /// `pwid`: Proportional widths.
+    pub const PWID: Tag = Tag::from_bytes(b"pwid");
+
+    /// `qwid`: Quarter widths.
+    pub const QWID: Tag = Tag::from_bytes(b"qwid");
+
+    /// `rand`: Randomize.
+    pub const RAND: Tag = Tag::from_bytes(b"rand");
+
+    /// `rclt`: Required contextual alternates.
+    pub const RCLT: Tag = Tag::from_bytes(b"rclt");
+
+    /// `rkrf`: Rakar forms.
+    pub const RKRF: Tag = Tag::from_bytes(b"rkrf");
+
+    /// `rlig`: Required ligatures.
+    pub const RLIG: Tag = Tag::from_bytes(b"rlig");
+
+    /// `rphf`: Reph form.
+    pub const RPHF: Tag = Tag::from_bytes(b"rphf");
+
+    /// `rtbd`: Right bounds.
+    pub const RTBD: Tag = Tag::from_bytes(b"rtbd");
+
+    /// `rtla`: Right-to-left alternates.
+    pub const RTLA: Tag = Tag::from_bytes(b"rtla");
+
+    /// `rtlm`: Right-to-left mirrored forms.
+    pub const RTLM: Tag = Tag::from_bytes(b"rtlm");
+
+    /// `ruby`: Ruby notation forms.
+    pub const RUBY: Tag = Tag::from_bytes(b"ruby");
+
+    /// `rvrn`: Required variation alternates.
+    pub const RVRN: Tag = Tag::from_bytes(b"rvrn");
+
+    /// `salt`: Stylistic alternates.
+    pub const SALT: Tag = Tag::from_bytes(b"salt");
+
+    /// `sinf`: Scientific inferiors.
+    pub const SINF: Tag = Tag::from_bytes(b"sinf");
+
+    /// `size`: Optical size.
+    pub const SIZE: Tag = Tag::from_bytes(b"size");
+
+    /// `smcp`: Small capitals.
+    pub const SMCP: Tag = Tag::from_bytes(b"smcp");
+
+    /// `smpl`: Simplified forms.
+    pub const SMPL: Tag = Tag::from_bytes(b"smpl");
+
+    /// `ss01`: Stylistic set 1.
+    pub const SS01: Tag = Tag::from_bytes(b"ss01");
+
+    /// `ss02`: Stylistic set 2.
+    pub const SS02: Tag = Tag::from_bytes(b"ss02");
+
+    /// `ss03`: Stylistic set 3.
+    pub const SS03: Tag = Tag::from_bytes(b"ss03");
+
+    /// `ss04`: Stylistic set 4.
+    pub const SS04: Tag = Tag::from_bytes(b"ss04");
+
+    /// `ss05`: Stylistic set 5.
+    pub const SS05: Tag = Tag::from_bytes(b"ss05");
+
+    /// `ss06`: Stylistic set 6.
+    pub const SS06: Tag = Tag::from_bytes(b"ss06");
+
+    /// `ss07`: Stylistic set 7.
+    pub const SS07: Tag = Tag::from_bytes(b"ss07");
+
+    /// `ss08`: Stylistic set 8.
+    pub const SS08: Tag = Tag::from_bytes(b"ss08");
+
+    /// `ss09`: Stylistic set 9.
+    pub const SS09: Tag = Tag::from_bytes(b"ss09");
+
+    /// `ss10`: Stylistic set 10.
+    pub const SS10: Tag = Tag::from_bytes(b"ss10");
+
+    /// `ss11`: Stylistic set 11.
+    pub const SS11: Tag = Tag::from_bytes(b"ss11");
+
+    /// `ss12`: Stylistic set 12.
+    pub const SS12: Tag = Tag::from_bytes(b"ss12");
+
+    /// `ss13`: Stylistic set 13.
+    pub const SS13: Tag = Tag::from_bytes(b"ss13");
+
+    /// `ss14`: Stylistic set 14.
+    pub const SS14: Tag = Tag::from_bytes(b"ss14");
+
+    /// `ss15`: Stylistic set 15.
+    pub const SS15: Tag = Tag::from_bytes(b"ss15");
+
+    /// `ss16`: Stylistic set 16.
+    pub const SS16: Tag = Tag::from_bytes(b"ss16");
+
+    /// `ss17`: Stylistic set 17.
+    pub const SS17: Tag = Tag::from_bytes(b"ss17");
+
+    /// `ss18`: Stylistic set 18.
+    pub const SS18: Tag = Tag::from_bytes(b"ss18");
+
+    /// `ss19`: Stylistic set 19.
+    pub const SS19: Tag = Tag::from_bytes(b"ss19");
+
+    /// `ss20`: Stylistic set 20.
+    pub const SS20: Tag = Tag::from_bytes(b"ss20");
+
+    /// `ssty`: Math script-style alternates.
+    pub const SSTY: Tag = Tag::from_bytes(b"ssty");
+
+    /// `stch`: Stretching glyph decomposition.
+    pub const STCH: Tag = Tag::from_bytes(b"stch");
+
+    /// `subs`: Subscript.
+    pub const SUBS: Tag = Tag::from_bytes(b"subs");
+
+    /// `sups`: Superscript.
+    pub const SUPS: Tag = Tag::from_bytes(b"sups");
+
+    /// `swsh`: Swash.
+    pub const SWSH: Tag = Tag::from_bytes(b"swsh");
+
+    /// `titl`: Titling.
+    pub const TITL: Tag = Tag::from_bytes(b"titl");
+
+    /// `tjmo`: Trailing jamo forms.
+    pub const TJMO: Tag = Tag::from_bytes(b"tjmo");
+
+    /// `tnam`: Traditional name forms.
+    pub const TNAM: Tag = Tag::from_bytes(b"tnam");
+
+    /// `tnum`: Tabular figures.
+    pub const TNUM: Tag = Tag::from_bytes(b"tnum");
+
+    /// `trad`: Traditional forms.
+    pub const TRAD: Tag = Tag::from_bytes(b"trad");
+
+    /// `twid`: Third widths.
+    pub const TWID: Tag = Tag::from_bytes(b"twid");
+
+    /// `unic`: Unicase.
+    pub const UNIC: Tag = Tag::from_bytes(b"unic");
+
+    /// `valt`: Alternate vertical metrics.
+    pub const VALT: Tag = Tag::from_bytes(b"valt");
+
+    /// `vapk`: Kerning for alternate proportional vertical metrics.
+    pub const VAPK: Tag = Tag::from_bytes(b"vapk");
+
+    /// `vatu`: Vattu variants.
+    pub const VATU: Tag = Tag::from_bytes(b"vatu");
+
+    /// `vchw`: Vertical contextual half-width spacing.
+    pub const VCHW: Tag = Tag::from_bytes(b"vchw");
+
+    /// `vert`: Vertical alternates.
+    pub const VERT: Tag = Tag::from_bytes(b"vert");
+
+    /// `vhal`: Alternate vertical half metrics.
+    pub const VHAL: Tag = Tag::from_bytes(b"vhal");
+
+    /// `vjmo`: Vowel jamo forms.
+    pub const VJMO: Tag = Tag::from_bytes(b"vjmo");
+
+    /// `vkna`: Vertical kana alternates.
+    pub const VKNA: Tag = Tag::from_bytes(b"vkna");
+
+    /// `vkrn`: Vertical kerning.
+    pub const VKRN: Tag = Tag::from_bytes(b"vkrn");
+
+    /// `vpal`: Proportional alternate vertical metrics.
+    pub const VPAL: Tag = Tag::from_bytes(b"vpal");
+
+    /// `vrt2`: Vertical alternates and rotation.
+    pub const VRT2: Tag = Tag::from_bytes(b"vrt2");
+
+    /// `vrtr`: Vertical alternates for rotation.
+    pub const VRTR: Tag = Tag::from_bytes(b"vrtr");
+
+    /// `zero`: Slashed zero.
+    pub const ZERO: Tag = Tag::from_bytes(b"zero");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_match_their_names() {
+        assert_eq!(table::OS2, Tag::new(b"OS/2"));
+        assert_eq!(table::CVT, Tag::new(b"cvt "));
+        assert_eq!(feature::SS20, Tag::new(b"ss20"));
+        assert_eq!(feature::character_variant(1), Tag::new(b"cv01"));
+        assert_eq!(feature::character_variant(99), Tag::new(b"cv99"));
+        assert!(table::CFF.is_valid());
+    }
+
+    #[test]
+    #[should_panic]
+    fn character_variant_zero_is_invalid() {
+        feature::character_variant(0);
+    }
+}
@@ -0,0 +1,535 @@
+use std::fmt;
+
+use crate::{sys, FontFace, GlyphId, Tag};
+
+/// Tables which browsers require every font to have.
+const REQUIRED_TABLES: [&[u8; 4]; 8] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+];
+
+/// Value from which the checksum of the whole font is subtracted to get `head.checkSumAdjustment`.
+const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+/// A problem found in a font face by [`FontFace::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// The font file is too short to contain a table directory, or its version is unknown.
+    InvalidHeader,
+    /// The table records in the table directory are not sorted by tag.
+    UnsortedTableDirectory,
+    /// The table appears more than once in the table directory.
+    DuplicateTable(Tag),
+    /// The table extends past the end of the font file.
+    TableOutOfBounds(Tag),
+    /// The table does not start at a four byte boundary.
+    MisalignedTable(Tag),
+    /// A required table is missing.
+    MissingTable(Tag),
+    /// The table is too short to contain the data it declares.
+    TableTooShort(Tag),
+    /// A field of the table has an invalid value.
+    InvalidField {
+        /// Tag of the table.
+        tag: Tag,
+        /// Name of the field, as in the OpenType spec.
+        field: &'static str,
+    },
+    /// The checksum stored in the table directory does not match the data of the table.
+    ChecksumMismatch {
+        /// Tag of the table.
+        tag: Tag,
+        /// Checksum stored in the table directory.
+        stored: u32,
+        /// Checksum computed from the table data.
+        computed: u32,
+    },
+    /// The `checkSumAdjustment` field of the `head` table does not match the data of the font.
+    ChecksumAdjustmentMismatch {
+        /// Value stored in the `head` table.
+        stored: u32,
+        /// Value computed from the font data.
+        computed: u32,
+    },
+    /// The number of glyphs described by the table does not match `maxp.numGlyphs`.
+    GlyphCountMismatch {
+        /// Tag of the table.
+        tag: Tag,
+        /// Number of glyphs according to `maxp`.
+        num_glyphs: usize,
+        /// Number of glyphs according to the table.
+        count: usize,
+    },
+    /// The offset of the glyph in `loca` is smaller than the offset of the preceding glyph.
+    LocaNotMonotonic {
+        /// Id of the glyph.
+        glyph: GlyphId,
+    },
+    /// The glyph extends past the end of the `glyf` table.
+    LocaOutOfBounds {
+        /// Id of the glyph.
+        glyph: GlyphId,
+    },
+    /// The character is mapped to a glyph which does not exist.
+    CmapGlyphOutOfRange {
+        /// The mapped character.
+        codepoint: char,
+        /// The glyph id the character is mapped to.
+        glyph: GlyphId,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn tag(tag: &Tag) -> String {
+            let tag: [u8; 4] = (*tag).into();
+            String::from_utf8_lossy(&tag).into_owned()
+        }
+
+        match self {
+            Self::InvalidHeader => write!(f, "Invalid font header"),
+            Self::UnsortedTableDirectory => write!(f, "Table directory is not sorted"),
+            Self::DuplicateTable(t) => write!(f, "Table {} appears more than once", tag(t)),
+            Self::TableOutOfBounds(t) => write!(f, "Table {} is out of bounds", tag(t)),
+            Self::MisalignedTable(t) => write!(f, "Table {} is not aligned", tag(t)),
+            Self::MissingTable(t) => write!(f, "Required table {} is missing", tag(t)),
+            Self::TableTooShort(t) => write!(f, "Table {} is too short", tag(t)),
+            Self::InvalidField { tag: t, field } => {
+                write!(f, "Field {}.{field} has an invalid value", tag(t))
+            }
+            Self::ChecksumMismatch {
+                tag: t,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "Checksum of table {} is {stored:#010x}, expected {computed:#010x}",
+                tag(t)
+            ),
+            Self::ChecksumAdjustmentMismatch { stored, computed } => write!(
+                f,
+                "head.checkSumAdjustment is {stored:#010x}, expected {computed:#010x}"
+            ),
+            Self::GlyphCountMismatch {
+                tag: t,
+                num_glyphs,
+                count,
+            } => write!(
+                f,
+                "Table {} describes {count} glyphs, but maxp.numGlyphs is {num_glyphs}",
+                tag(t)
+            ),
+            Self::LocaNotMonotonic { glyph } => {
+                write!(f, "Offset of glyph {glyph} in loca is not monotonic")
+            }
+            Self::LocaOutOfBounds { glyph } => write!(f, "Glyph {glyph} is out of bounds of glyf"),
+            Self::CmapGlyphOutOfRange { codepoint, glyph } => write!(
+                f,
+                "Character {codepoint:?} is mapped to non-existent glyph {glyph}"
+            ),
+        }
+    }
+}
+
+impl<'a> FontFace<'a> {
+    /// Checks the font face for structural problems which would make e.g. browsers reject it.
+    ///
+    /// The font data is parsed independently of HarfBuzz, and the following is checked:
+    /// - The table directory is well-formed, and all tables lie within the file.
+    /// - Table checksums and `head.checkSumAdjustment` match the data.
+    /// - All tables required by browsers exist, and the `head`, `hhea` and `maxp` tables have valid values.
+    /// - `hmtx` and `loca` describe as many glyphs as `maxp`.
+    /// - Offsets in `loca` are monotonic and lie within `glyf`.
+    /// - `cmap` maps characters only to existing glyphs.
+    ///
+    /// Returns the list of found issues, which is empty if the font face is valid. The face must be backed by font
+    /// data, so [preprocessed faces](FontFace::preprocess_for_subsetting) cannot be validated.
+    ///
+    /// # Example
+    /// ```
+    /// # use hb_subset::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = FontFace::new(Blob::from_file("tests/fonts/NotoSans.ttf")?)?;
+    /// let mut subset = SubsetInput::new()?;
+    /// subset.unicode_set().insert('a');
+    /// let new_font = subset.subset_font(&font)?;
+    /// for issue in new_font.validate() {
+    ///     println!("{issue}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let blob = self.underlying_blob();
+        let index = unsafe { sys::hb_face_get_index(self.as_raw()) };
+        let mut issues = validate_data(&blob, index);
+
+        // HarfBuzz sanitizes cmap on its own, so it can be used for reading the mapping
+        let num_glyphs = self.glyph_count() as u32;
+        if let Ok(mapping) = self.nominal_glyph_mapping() {
+            let mut out_of_range = mapping
+                .iter()
+                .filter(|&(_, glyph)| glyph.to_u32() >= num_glyphs)
+                .collect::<Vec<_>>();
+            out_of_range.sort_unstable();
+            issues.extend(out_of_range.into_iter().map(|(codepoint, glyph)| {
+                ValidationIssue::CmapGlyphOutOfRange { codepoint, glyph }
+            }));
+        }
+        issues
+    }
+}
+
+/// A table located in the font data.
+struct Table<'d> {
+    tag: Tag,
+    data: &'d [u8],
+}
+
+/// Validates the structure of raw font data.
+fn validate_data(data: &[u8], index: u32) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    // Locate the offset table of the face within a collection
+    let (start, is_collection) = if data.starts_with(b"ttcf") {
+        match read_u32(data, 12 + 4 * index as usize) {
+            Some(start) => (start as usize, true),
+            None => return vec![ValidationIssue::InvalidHeader],
+        }
+    } else {
+        (0, false)
+    };
+
+    let (Some(version), Some(num_tables)) = (read_u32(data, start), read_u16(data, start + 4))
+    else {
+        return vec![ValidationIssue::InvalidHeader];
+    };
+    if !matches!(version, 0x00010000 | 0x4F54544F | 0x74727565) || num_tables == 0 {
+        return vec![ValidationIssue::InvalidHeader];
+    }
+
+    // Table directory
+    let mut tables = Vec::<Table>::new();
+    for i in 0..num_tables as usize {
+        let record = start + 12 + 16 * i;
+        let (Some(tag), Some(checksum), Some(offset), Some(length)) = (
+            read_u32(data, record),
+            read_u32(data, record + 4),
+            read_u32(data, record + 8),
+            read_u32(data, record + 12),
+        ) else {
+            return vec![ValidationIssue::InvalidHeader];
+        };
+        let tag = Tag::from(tag);
+
+        if let Some(previous) = tables.last() {
+            if previous.tag == tag {
+                issues.push(ValidationIssue::DuplicateTable(tag));
+            } else if previous.tag > tag
+                && !issues.contains(&ValidationIssue::UnsortedTableDirectory)
+            {
+                issues.push(ValidationIssue::UnsortedTableDirectory);
+            }
+        }
+        let Some(table) = (offset as usize)
+            .checked_add(length as usize)
+            .and_then(|end| data.get(offset as usize..end))
+        else {
+            issues.push(ValidationIssue::TableOutOfBounds(tag));
+            continue;
+        };
+        if offset % 4 != 0 {
+            issues.push(ValidationIssue::MisalignedTable(tag));
+        }
+
+        let computed = if tag == Tag::new(b"head") {
+            checksum_without_adjustment(table)
+        } else {
+            table_checksum(table)
+        };
+        if computed != checksum {
+            issues.push(ValidationIssue::ChecksumMismatch {
+                tag,
+                stored: checksum,
+                computed,
+            });
+        }
+        tables.push(Table { tag, data: table });
+    }
+    let find = |tag: &[u8; 4]| {
+        tables
+            .iter()
+            .find(|table| table.tag == Tag::new(tag))
+            .map(|table| table.data)
+    };
+
+    let mut required = REQUIRED_TABLES.to_vec();
+    if version == 0x4F54544F {
+        // CFF fonts can have either version of the table
+        required.push(if find(b"CFF2").is_some() {
+            b"CFF2"
+        } else {
+            b"CFF "
+        });
+    } else {
+        required.extend([b"glyf", b"loca"]);
+    }
+    for tag in required {
+        if find(tag).is_none() {
+            issues.push(ValidationIssue::MissingTable(Tag::new(tag)));
+        }
+    }
+
+    // head
+    let mut index_to_loc_format = None;
+    if let Some(head) = find(b"head") {
+        let tag = Tag::new(b"head");
+        if head.len() < 54 {
+            issues.push(ValidationIssue::TableTooShort(tag));
+        } else {
+            if read_u32(head, 12) != Some(0x5F0F3CF5) {
+                issues.push(ValidationIssue::InvalidField {
+                    tag,
+                    field: "magicNumber",
+                });
+            }
+            if !matches!(read_u16(head, 18), Some(16..=16384)) {
+                issues.push(ValidationIssue::InvalidField {
+                    tag,
+                    field: "unitsPerEm",
+                });
+            }
+            match read_u16(head, 50) {
+                Some(format @ (0 | 1)) => index_to_loc_format = Some(format),
+                _ => issues.push(ValidationIssue::InvalidField {
+                    tag,
+                    field: "indexToLocFormat",
+                }),
+            }
+
+            if !is_collection {
+                let stored = read_u32(head, 8).unwrap();
+                let computed =
+                    CHECKSUM_MAGIC.wrapping_sub(table_checksum(data).wrapping_sub(stored));
+                if stored != computed {
+                    issues.push(ValidationIssue::ChecksumAdjustmentMismatch { stored, computed });
+                }
+            }
+        }
+    }
+
+    // maxp
+    let mut num_glyphs = None;
+    if let Some(maxp) = find(b"maxp") {
+        let tag = Tag::new(b"maxp");
+        match (read_u32(maxp, 0), read_u16(maxp, 4)) {
+            (Some(0x00005000), Some(count)) => num_glyphs = Some(count as usize),
+            (Some(0x00010000), Some(count)) if maxp.len() >= 32 => {
+                num_glyphs = Some(count as usize)
+            }
+            (Some(0x00005000 | 0x00010000) | None, _) => {
+                issues.push(ValidationIssue::TableTooShort(tag))
+            }
+            (Some(_), _) => issues.push(ValidationIssue::InvalidField {
+                tag,
+                field: "version",
+            }),
+        }
+        if num_glyphs == Some(0) {
+            issues.push(ValidationIssue::InvalidField {
+                tag,
+                field: "numGlyphs",
+            });
+            num_glyphs = None;
+        }
+    }
+
+    // hhea and hmtx
+    if let (Some(hhea), Some(num_glyphs)) = (find(b"hhea"), num_glyphs) {
+        let tag = Tag::new(b"hhea");
+        match read_u16(hhea, 34) {
+            None => issues.push(ValidationIssue::TableTooShort(tag)),
+            Some(num_h_metrics) if num_h_metrics == 0 || num_h_metrics as usize > num_glyphs => {
+                issues.push(ValidationIssue::InvalidField {
+                    tag,
+                    field: "numberOfHMetrics",
+                })
+            }
+            Some(num_h_metrics) => {
+                let num_h_metrics = num_h_metrics as usize;
+                if let Some(hmtx) = find(b"hmtx") {
+                    let expected = 4 * num_h_metrics + 2 * (num_glyphs - num_h_metrics);
+                    if hmtx.len() < expected {
+                        issues.push(ValidationIssue::GlyphCountMismatch {
+                            tag: Tag::new(b"hmtx"),
+                            num_glyphs,
+                            count: num_h_metrics + hmtx.len().saturating_sub(4 * num_h_metrics) / 2,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // loca and glyf
+    if let (Some(loca), Some(glyf), Some(format), Some(num_glyphs)) = (
+        find(b"loca"),
+        find(b"glyf"),
+        index_to_loc_format,
+        num_glyphs,
+    ) {
+        let entry_size = if format == 0 { 2 } else { 4 };
+        let count = (loca.len() / entry_size).saturating_sub(1);
+        if count < num_glyphs {
+            issues.push(ValidationIssue::GlyphCountMismatch {
+                tag: Tag::new(b"loca"),
+                num_glyphs,
+                count,
+            });
+        }
+
+        let offset = |glyph: usize| {
+            if format == 0 {
+                read_u16(loca, 2 * glyph).map(|offset| 2 * offset as usize)
+            } else {
+                read_u32(loca, 4 * glyph).map(|offset| offset as usize)
+            }
+        };
+        let mut previous = 0;
+        for glyph in 0..=count.min(num_glyphs) {
+            let Some(offset) = offset(glyph) else { break };
+            if offset < previous {
+                issues.push(ValidationIssue::LocaNotMonotonic {
+                    glyph: GlyphId::new(glyph as u32),
+                });
+            } else if offset > glyf.len() {
+                issues.push(ValidationIssue::LocaOutOfBounds {
+                    glyph: GlyphId::new(glyph.saturating_sub(1) as u32),
+                });
+                break;
+            }
+            previous = offset;
+        }
+    }
+
+    issues
+}
+
+/// Computes the OpenType checksum of the data, i.e. the sum of big-endian 32-bit words padded with zeroes.
+fn table_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Computes the checksum of the `head` table, in which `checkSumAdjustment` is treated as zero.
+fn checksum_without_adjustment(head: &[u8]) -> u32 {
+    table_checksum(head).wrapping_sub(read_u32(head, 8).unwrap_or(0))
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob, SubsetInput};
+
+    /// Finds the offset of the table in the font data.
+    fn table_offset(data: &[u8], tag: &[u8; 4]) -> usize {
+        let num_tables = read_u16(data, 4).unwrap() as usize;
+        let record = (0..num_tables)
+            .map(|i| 12 + 16 * i)
+            .find(|&record| &data[record..record + 4] == tag)
+            .unwrap();
+        read_u32(data, record + 8).unwrap() as usize
+    }
+
+    fn subset_data() -> Vec<u8> {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        let mut subset = SubsetInput::new().unwrap();
+        subset.unicode_set().insert_range('a'..='z');
+        let new_font = subset.subset_font(&font).unwrap();
+        let data = new_font.underlying_blob().to_vec();
+        data
+    }
+
+    #[test]
+    fn valid_fonts_have_no_issues() {
+        let font = FontFace::new(Blob::from_file(NOTO_SANS).unwrap()).unwrap();
+        assert_eq!(font.validate(), []);
+
+        let data = subset_data();
+        let subset = FontFace::new(Blob::from_bytes(&data).unwrap()).unwrap();
+        assert_eq!(subset.validate(), []);
+    }
+
+    #[test]
+    fn corrupted_data_is_detected() {
+        let mut data = subset_data();
+        let glyf = table_offset(&data, b"glyf");
+        data[glyf + 10] ^= 0xff;
+        let issues = validate_data(&data, 0);
+        assert!(matches!(
+            issues[..],
+            [
+                ValidationIssue::ChecksumMismatch { tag, .. },
+                ValidationIssue::ChecksumAdjustmentMismatch { .. }
+            ] if tag == Tag::new(b"glyf")
+        ));
+    }
+
+    #[test]
+    fn broken_loca_is_detected() {
+        let mut data = subset_data();
+        let head = table_offset(&data, b"head");
+        let loca = table_offset(&data, b"loca");
+        let entry_size = if read_u16(&data, head + 50).unwrap() == 0 {
+            2
+        } else {
+            4
+        };
+        // Make the offset of glyph 2 smaller than that of glyph 1
+        data[loca + 2 * entry_size..loca + 3 * entry_size].fill(0);
+        let issues = validate_data(&data, 0);
+        assert!(issues.contains(&ValidationIssue::LocaNotMonotonic {
+            glyph: GlyphId::new(2)
+        }));
+    }
+
+    #[test]
+    fn glyph_count_mismatch_is_detected() {
+        let mut data = subset_data();
+        let maxp = table_offset(&data, b"maxp");
+        let num_glyphs = read_u16(&data, maxp + 4).unwrap();
+        data[maxp + 4..maxp + 6].copy_from_slice(&(num_glyphs + 10).to_be_bytes());
+        let issues = validate_data(&data, 0);
+        assert!(issues.contains(&ValidationIssue::GlyphCountMismatch {
+            tag: Tag::new(b"loca"),
+            num_glyphs: num_glyphs as usize + 10,
+            count: num_glyphs as usize,
+        }));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            ValidationIssue::GlyphCountMismatch { tag, .. } if *tag == Tag::new(b"hmtx")
+        )));
+    }
+
+    #[test]
+    fn truncated_font_is_rejected() {
+        assert_eq!(validate_data(&[], 0), [ValidationIssue::InvalidHeader]);
+        let data = subset_data();
+        assert!(validate_data(&data[..data.len() - 100], 0)
+            .iter()
+            .any(|issue| matches!(issue, ValidationIssue::TableOutOfBounds(_))));
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use crate::{sys, UnsupportedError};
+
+/// Version of the HarfBuzz library.
+///
+/// Versions are ordered as in semantic versioning, so they can be compared to find out whether the linked library is
+/// recent enough.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// let version = hb_subset::harfbuzz_version();
+/// assert!(version >= HarfBuzzVersion::new(7, 0, 0));
+/// println!("Using HarfBuzz {version}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HarfBuzzVersion {
+    /// Major version.
+    pub major: u32,
+    /// Minor version.
+    pub minor: u32,
+    /// Micro version.
+    pub micro: u32,
+}
+
+impl HarfBuzzVersion {
+    /// Constructs a new version.
+    pub const fn new(major: u32, minor: u32, micro: u32) -> Self {
+        Self {
+            major,
+            minor,
+            micro,
+        }
+    }
+}
+
+impl fmt::Display for HarfBuzzVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.micro)
+    }
+}
+
+/// Gets the version of the HarfBuzz library this crate is running against.
+///
+/// When HarfBuzz is linked dynamically, this may differ from the version the crate was compiled against.
+#[doc(alias = "hb_version")]
+pub fn harfbuzz_version() -> HarfBuzzVersion {
+    let (mut major, mut minor, mut micro) = (0, 0, 0);
+    unsafe { sys::hb_version(&mut major, &mut minor, &mut micro) };
+    HarfBuzzVersion::new(major, minor, micro)
+}
+
+/// A feature of this crate which is available only with recent enough versions of HarfBuzz.
+///
+/// A capability is supported if both the HarfBuzz this crate was compiled against and the HarfBuzz it is running
+/// against provide it. Using an unsupported capability results in an [`UnsupportedError`] instead of a link failure.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut subset = SubsetInput::new()?;
+/// if Capability::NoLayoutClosure.is_supported() {
+///     subset.flags().no_layout_closure();
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Capability {
+    /// Pinning variation axes with [`SubsetInput::pin_axis`](crate::SubsetInput::pin_axis). The bundled HarfBuzz
+    /// leaves out variation support when built with feature `bundled-lean` or `bundled-mini`.
+    AxisPinning,
+    /// Skipping the layout closure with [`Flags::no_layout_closure`](crate::Flags::no_layout_closure). Older versions
+    /// of HarfBuzz silently ignore the flag.
+    NoLayoutClosure,
+    /// Overriding name table entries with `SubsetInput::override_name_table`. This requires feature `experimental`
+    /// and the bundled HarfBuzz, which is then built with experimental APIs enabled. A system HarfBuzz is never assumed
+    /// to provide experimental APIs, as distributions usually build it without them. Features `bundled-lean` and
+    /// `bundled-mini` leave out the name table support the overrides need.
+    NameTableOverride,
+    /// Limiting the memory subsetting may use with
+    /// [`SubsetInput::set_memory_budget`](crate::SubsetInput::set_memory_budget). This requires the bundled HarfBuzz
+    /// built with feature `custom-allocator`, so that its allocations can be tracked.
+    MemoryBudget,
+}
+
+impl Capability {
+    /// Gets the oldest version of HarfBuzz which provides the capability.
+    pub const fn min_version(self) -> HarfBuzzVersion {
+        match self {
+            Capability::AxisPinning => HarfBuzzVersion::new(7, 0, 0),
+            Capability::NoLayoutClosure => HarfBuzzVersion::new(7, 2, 0),
+            Capability::NameTableOverride => HarfBuzzVersion::new(7, 0, 0),
+            Capability::MemoryBudget => HarfBuzzVersion::new(7, 0, 0),
+        }
+    }
+
+    /// Checks whether the capability was available when compiling this crate.
+    ///
+    /// The build script sets a cfg for each capability the HarfBuzz headers provide.
+    const fn is_compiled(self) -> bool {
+        match self {
+            Capability::AxisPinning => cfg!(not(hb_subset_lean)),
+            Capability::NoLayoutClosure => cfg!(hb_subset_no_layout_closure),
+            Capability::NameTableOverride => cfg!(hb_subset_override_name_table),
+            Capability::MemoryBudget => cfg!(hb_subset_rust_allocator),
+        }
+    }
+
+    /// Checks whether the capability is supported by HarfBuzz.
+    pub fn is_supported(self) -> bool {
+        self.is_compiled() && harfbuzz_version() >= self.min_version()
+    }
+
+    /// Returns an error if the capability is not supported by HarfBuzz.
+    pub fn check(self) -> Result<(), UnsupportedError> {
+        if self.is_supported() {
+            Ok(())
+        } else {
+            Err(UnsupportedError {
+                capability: self,
+                version: harfbuzz_version(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Capability::AxisPinning => "Pinning variation axes",
+            Capability::NoLayoutClosure => "Skipping layout closure",
+            Capability::NameTableOverride => "Name table overrides",
+            Capability::MemoryBudget => "Memory budget",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_matches_version_string() {
+        let version = harfbuzz_version();
+        let string = unsafe { std::ffi::CStr::from_ptr(sys::hb_version_string()) };
+        assert_eq!(version.to_string(), string.to_str().unwrap());
+        assert!(version >= HarfBuzzVersion::new(7, 0, 0));
+    }
+
+    #[test]
+    fn versions_are_ordered() {
+        assert!(HarfBuzzVersion::new(7, 2, 0) > HarfBuzzVersion::new(7, 0, 9));
+        assert!(HarfBuzzVersion::new(10, 0, 0) > HarfBuzzVersion::new(9, 9, 9));
+        assert_eq!(HarfBuzzVersion::new(8, 3, 1).to_string(), "8.3.1");
+    }
+
+    #[test]
+    fn capabilities_follow_version() {
+        let capability = Capability::NoLayoutClosure;
+        assert_eq!(
+            capability.is_supported(),
+            harfbuzz_version() >= capability.min_version()
+        );
+        assert_eq!(capability.check().is_ok(), capability.is_supported());
+        assert_eq!(
+            Capability::NameTableOverride.is_supported(),
+            cfg!(hb_subset_override_name_table)
+        );
+        assert_eq!(
+            Capability::AxisPinning.is_supported(),
+            cfg!(not(hb_subset_lean))
+        );
+        assert_eq!(
+            Capability::MemoryBudget.is_supported(),
+            cfg!(hb_subset_rust_allocator)
+        );
+    }
+
+    #[test]
+    fn unsupported_error_names_version() {
+        let err = UnsupportedError {
+            capability: Capability::NoLayoutClosure,
+            version: HarfBuzzVersion::new(7, 1, 0),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Skipping layout closure is unsupported by HarfBuzz 7.1"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! JavaScript bindings for subsetting fonts in browsers and edge workers.
+//!
+//! The bindings are generated with [`wasm-bindgen`](https://docs.rs/wasm-bindgen), and available with feature
+//! `wasm-bindgen`. The module exports function [`subset`] together with its configuration type [`SubsetOptions`]:
+//! ```js
+//! import { subset, SubsetOptions } from "hb-subset";
+//!
+//! const options = new SubsetOptions();
+//! options.retainHinting = false;
+//! options.dropTables(["GPOS", "GSUB"]);
+//! const subsetFont = subset(new Uint8Array(font), "Hello, world!", options);
+//! ```
+
+use std::mem;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    AxisLocation, Blob, EmbeddingPolicy, FontFace, SubsetInputBuilder, SubsettingError, Tag,
+};
+
+/// Configuration of [`subset`].
+///
+/// A new configuration retains hinting, layout closure and the default layout features, like
+/// [`SubsetInput::new`](crate::SubsetInput::new).
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct SubsetOptions {
+    builder: SubsetInputBuilder,
+}
+
+#[wasm_bindgen]
+impl SubsetOptions {
+    /// Creates a default configuration.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether hinting instructions are retained.
+    #[wasm_bindgen(setter = retainHinting)]
+    pub fn set_retain_hinting(&mut self, retain: bool) {
+        self.update(|builder| {
+            builder.flags(|flags| {
+                if retain {
+                    flags.retain_hinting()
+                } else {
+                    flags.remove_hinting()
+                }
+            })
+        });
+    }
+
+    /// Sets whether glyph names are retained.
+    #[wasm_bindgen(setter = retainGlyphNames)]
+    pub fn set_retain_glyph_names(&mut self, retain: bool) {
+        self.update(|builder| {
+            builder.flags(|flags| {
+                if retain {
+                    flags.retain_glyph_names()
+                } else {
+                    flags.remove_glyph_names()
+                }
+            })
+        });
+    }
+
+    /// Sets whether glyph indices are retained instead of renumbering the glyphs of the subset.
+    #[wasm_bindgen(setter = retainGlyphIndices)]
+    pub fn set_retain_glyph_indices(&mut self, retain: bool) {
+        self.update(|builder| {
+            builder.flags(|flags| {
+                if retain {
+                    flags.retain_glyph_indices()
+                } else {
+                    flags.remap_glyph_indices()
+                }
+            })
+        });
+    }
+
+    /// Sets whether glyphs reachable through layout substitutions, e.g. ligatures, are retained.
+    #[wasm_bindgen(setter = layoutClosure)]
+    pub fn set_layout_closure(&mut self, retain: bool) {
+        self.update(|builder| {
+            builder.flags(|flags| {
+                if retain {
+                    flags.retain_layout_closure()
+                } else {
+                    flags.no_layout_closure()
+                }
+            })
+        });
+    }
+
+    /// Sets whether subsetting fails if the embedding permissions of the font do not allow it.
+    #[wasm_bindgen(setter = enforceEmbeddingPermissions)]
+    pub fn set_enforce_embedding_permissions(&mut self, enforce: bool) {
+        let policy = if enforce {
+            EmbeddingPolicy::Enforce
+        } else {
+            EmbeddingPolicy::Ignore
+        };
+        self.update(|builder| builder.embedding_policy(policy));
+    }
+
+    /// Sets whether the produced subset is validated.
+    #[wasm_bindgen(setter = validateOutput)]
+    pub fn set_validate_output(&mut self, validate: bool) {
+        self.update(|builder| builder.validate_output(validate));
+    }
+
+    /// Drops the tables with the given tags from the subset.
+    #[wasm_bindgen(js_name = dropTables)]
+    pub fn drop_tables(&mut self, tables: Vec<String>) -> Result<(), JsError> {
+        let tables = parse_tags(&tables)?;
+        self.update(|builder| builder.drop_tables(tables));
+        Ok(())
+    }
+
+    /// Retains the layout features with the given tags in addition to the default ones.
+    #[wasm_bindgen(js_name = keepFeatures)]
+    pub fn keep_features(&mut self, features: Vec<String>) -> Result<(), JsError> {
+        let features = parse_tags(&features)?;
+        self.update(|builder| builder.keep_features(features));
+        Ok(())
+    }
+
+    /// Pins a variation axis to the given location, or to its default location if none is given.
+    #[wasm_bindgen(js_name = pinAxis)]
+    pub fn pin_axis(&mut self, axis: &str, location: Option<f32>) -> Result<(), JsError> {
+        let axis = axis.parse::<Tag>()?;
+        let location = location.map_or(AxisLocation::Default, AxisLocation::Value);
+        self.update(|builder| builder.pin_axis(axis, location));
+        Ok(())
+    }
+}
+
+impl SubsetOptions {
+    fn update(&mut self, f: impl FnOnce(SubsetInputBuilder) -> SubsetInputBuilder) {
+        self.builder = f(mem::take(&mut self.builder));
+    }
+}
+
+fn parse_tags(tags: &[String]) -> Result<Vec<Tag>, JsError> {
+    tags.iter()
+        .map(|tag| tag.parse().map_err(JsError::from))
+        .collect()
+}
+
+/// Creates a subset of a font which retains the characters of the given text.
+///
+/// The font is given as the bytes of an OpenType font file, e.g. a `Uint8Array`, and the subset is returned in the same
+/// format.
+#[wasm_bindgen]
+pub fn subset(font: &[u8], text: &str, options: &SubsetOptions) -> Result<Vec<u8>, JsError> {
+    subset_with_options(font, text, options).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn subset_with_options(
+    font: &[u8],
+    text: &str,
+    options: &SubsetOptions,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let input = options.builder.clone().text(text).build()?;
+    let font = FontFace::new(Blob::from_bytes(font).map_err(|_| SubsettingError::Failed)?)?;
+    let new_font = input.subset_font(&font)?;
+    let new_font = new_font.underlying_blob().to_vec();
+    Ok(new_font)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::NOTO_SANS;
+
+    #[test]
+    fn options_configure_subset() {
+        let font = std::fs::read(NOTO_SANS).unwrap();
+        let default = subset_with_options(&font, "fi", &SubsetOptions::new()).unwrap();
+
+        let mut options = SubsetOptions::new();
+        options.set_retain_hinting(false);
+        options.drop_tables(vec!["GPOS".into()]).unwrap();
+        let small = subset_with_options(&font, "fi", &options).unwrap();
+        assert!(small.len() < default.len());
+
+        let default = FontFace::new(Blob::from_bytes(&default).unwrap()).unwrap();
+        let small = FontFace::new(Blob::from_bytes(&small).unwrap()).unwrap();
+        assert!(!default.table(Tag::new(b"GPOS")).is_empty());
+        assert!(small.table(Tag::new(b"GPOS")).is_empty());
+        assert!(small.table(Tag::new(b"prep")).is_empty());
+    }
+}
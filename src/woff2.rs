@@ -0,0 +1,190 @@
+//! Encoding fonts as [WOFF2](https://www.w3.org/TR/WOFF2/).
+//!
+//! HarfBuzz produces plain OpenType (sfnt) fonts. For serving fonts on the web, WOFF2 is usually preferred, as its
+//! Brotli compression makes fonts considerably smaller. This module implements a WOFF2 encoder which applies no table
+//! transforms. Transforms of `glyf`/`loca` would save a few more percent, but null-transformed files are valid WOFF2
+//! and are supported by all browsers.
+
+use std::io::Write;
+
+use crate::Woff2EncodingError;
+
+/// Size of the sfnt header.
+const SFNT_HEADER_SIZE: usize = 12;
+/// Size of a single table record in the sfnt table directory.
+const SFNT_TABLE_RECORD_SIZE: usize = 16;
+/// Size of the WOFF2 header.
+const WOFF2_HEADER_SIZE: usize = 48;
+/// Flag byte value which tells that an arbitrary tag follows.
+const ARBITRARY_TAG: u8 = 0x3f;
+/// Transformation version which means "no transform" for `glyf` and `loca` tables.
+const GLYF_LOCA_NULL_TRANSFORM: u8 = 3 << 6;
+
+/// Encodes an OpenType font as WOFF2.
+///
+/// # Example
+/// ```
+/// # use hb_subset::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = std::fs::read("tests/fonts/NotoSans.ttf")?;
+/// let subset = hb_subset::subset(&font, "abc".chars())?;
+/// let woff2 = hb_subset::woff2::encode(&subset)?;
+/// assert_eq!(&woff2[..4], b"wOF2");
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode(sfnt: &[u8]) -> Result<Vec<u8>, Woff2EncodingError> {
+    let flavor = read_u32(sfnt, 0)?;
+    let num_tables = read_u16(sfnt, 4)?;
+
+    let mut tables = Vec::with_capacity(num_tables as usize);
+    for i in 0..num_tables as usize {
+        let record = SFNT_HEADER_SIZE + i * SFNT_TABLE_RECORD_SIZE;
+        let tag = read_u32(sfnt, record)?;
+        let offset = read_u32(sfnt, record + 8)? as usize;
+        let length = read_u32(sfnt, record + 12)? as usize;
+        let data = sfnt
+            .get(offset..offset.checked_add(length).ok_or(Woff2EncodingError)?)
+            .ok_or(Woff2EncodingError)?;
+        tables.push((tag, data));
+    }
+    tables.sort_by_key(|&(tag, _)| tag);
+
+    // Table directory
+    let mut directory = Vec::new();
+    let mut total_sfnt_size = SFNT_HEADER_SIZE + SFNT_TABLE_RECORD_SIZE * tables.len();
+    for &(tag, data) in &tables {
+        let flags = if &tag.to_be_bytes() == b"glyf" || &tag.to_be_bytes() == b"loca" {
+            ARBITRARY_TAG | GLYF_LOCA_NULL_TRANSFORM
+        } else {
+            ARBITRARY_TAG
+        };
+        directory.push(flags);
+        directory.extend_from_slice(&tag.to_be_bytes());
+        write_base128(&mut directory, data.len() as u32);
+        total_sfnt_size += (data.len() + 3) & !3;
+    }
+
+    // Compressed font data. Tables are concatenated without padding.
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        for &(_, data) in &tables {
+            writer.write_all(data).map_err(|_| Woff2EncodingError)?;
+        }
+    }
+
+    let length = (WOFF2_HEADER_SIZE + directory.len() + compressed.len() + 3) & !3;
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"wOF2");
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(length as u32).to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes()); // reserved
+    out.extend_from_slice(&(total_sfnt_size as u32).to_be_bytes());
+    out.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes()); // majorVersion
+    out.extend_from_slice(&0u16.to_be_bytes()); // minorVersion
+    out.extend_from_slice(&[0; 20]); // No metadata nor private data
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&compressed);
+    out.resize(length, 0);
+    Ok(out)
+}
+
+/// Writes a `UIntBase128` value.
+fn write_base128(out: &mut Vec<u8>, value: u32) {
+    let mut bytes = 1;
+    while bytes < 5 && value >> (7 * bytes) != 0 {
+        bytes += 1;
+    }
+    for i in (0..bytes).rev() {
+        let mut byte = ((value >> (7 * i)) & 0x7f) as u8;
+        if i != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Woff2EncodingError> {
+    let bytes = data.get(offset..offset + 2).ok_or(Woff2EncodingError)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Woff2EncodingError> {
+    let bytes = data.get(offset..offset + 4).ok_or(Woff2EncodingError)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::{tests::NOTO_SANS, Blob, FontFace, Tag};
+
+    /// Reads a `UIntBase128` value, returning it and the number of bytes read.
+    fn read_base128(data: &[u8]) -> (u32, usize) {
+        let mut value = 0;
+        for (i, byte) in data.iter().enumerate() {
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return (value, i + 1);
+            }
+        }
+        panic!("Unterminated UIntBase128");
+    }
+
+    #[test]
+    fn base128_works() {
+        for value in [0, 1, 127, 128, 16383, 16384, 63 << 21, u32::MAX] {
+            let mut out = Vec::new();
+            write_base128(&mut out, value);
+            assert_eq!(read_base128(&out), (value, out.len()));
+        }
+        let mut out = Vec::new();
+        write_base128(&mut out, 128);
+        assert_eq!(out, [0x81, 0x00]);
+    }
+
+    #[test]
+    fn encoded_tables_decompress_to_original() {
+        let sfnt = std::fs::read(NOTO_SANS).unwrap();
+        let woff2 = encode(&sfnt).unwrap();
+        assert_eq!(&woff2[0..4], b"wOF2");
+        assert_eq!(woff2.len() % 4, 0);
+        assert_eq!(read_u32(&woff2, 8).unwrap() as usize, woff2.len());
+        assert!(woff2.len() < sfnt.len());
+
+        let font = FontFace::new(Blob::from_bytes(&sfnt).unwrap()).unwrap();
+        let num_tables = read_u16(&woff2, 12).unwrap() as usize;
+        assert_eq!(num_tables, font.table_tags().len());
+
+        // Walk the table directory
+        let mut pos = WOFF2_HEADER_SIZE;
+        let mut expected = Vec::new();
+        for _ in 0..num_tables {
+            assert_eq!(woff2[pos] & 0x3f, ARBITRARY_TAG);
+            let tag = Tag::from(read_u32(&woff2, pos + 1).unwrap());
+            let (length, len_bytes) = read_base128(&woff2[pos + 5..]);
+            let table = font.table(tag);
+            assert_eq!(length as usize, table.len());
+            expected.extend_from_slice(&table);
+            pos += 5 + len_bytes;
+        }
+
+        let compressed_size = read_u32(&woff2, 20).unwrap() as usize;
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(&woff2[pos..pos + compressed_size], 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, expected);
+    }
+
+    #[test]
+    fn invalid_font_is_rejected() {
+        assert!(encode(&[]).is_err());
+        assert!(encode(&[0, 1, 0, 0, 0, 1]).is_err());
+    }
+}